@@ -1,12 +1,16 @@
 use crate::{
-    components::{Position, Render},
+    components::{Hidden, Position, Render},
     constants::{colors, consoles},
     map_builder::map::{Map, TileStatus, TileType},
 };
-use rltk::{ColorPair, Point, Rltk};
+use rltk::{ColorPair, Point, Rltk, RGB};
 use specs::{Join, World, WorldExt};
 
 const EDGE_BUFFER: usize = 2;
+///How bright visible things are drawn in the darkest light
+const MIN_BRIGHTNESS: f32 = 0.4;
+///How bright remembered things are drawn out of view
+const MEMORY_BRIGHTNESS: f32 = 0.5;
 
 pub fn render(ecs: &World, ctx: &mut Rltk) {
     let map = ecs.fetch::<Map>();
@@ -28,13 +32,43 @@ pub fn render(ecs: &World, ctx: &mut Rltk) {
 
     let positions = ecs.read_storage::<Position>();
     let renderables = ecs.read_storage::<Render>();
+    let hidden = ecs.read_storage::<Hidden>();
     let map = ecs.fetch::<Map>();
 
-    let mut data = (&positions, &renderables).join().collect::<Vec<_>>();
+    let mut data = (&positions, &renderables, !&hidden)
+        .join()
+        .map(|(pos, render, ())| (pos, render))
+        .collect::<Vec<_>>();
     data.sort_by(|&a, &b| b.1.render_order.cmp(&a.1.render_order));
 
     ctx.set_active_console(consoles::CHAR_CONSOLE);
 
+    //Things seen before are drawn dimmed where they were last seen, with monsters losing their color
+    for (ty, y) in (min_y..max_y).zip(0..).skip(EDGE_BUFFER) {
+        for (tx, x) in (min_x..max_x).zip(0..).skip(EDGE_BUFFER) {
+            if tx > 0 && tx < map.width && ty > 0 && ty < map.height {
+                let idx = map.xy_idx(tx, ty);
+                if map.is_tile_status_set(idx, TileStatus::Visible) {
+                    continue;
+                }
+                if let Some(memory) = map.memories[idx] {
+                    let color = if memory.is_monster {
+                        memory.color.to_greyscale()
+                    } else {
+                        memory.color
+                    };
+                    ctx.set(
+                        x,
+                        y,
+                        color * MEMORY_BRIGHTNESS,
+                        RGB::from(colors::BACKGROUND),
+                        memory.glyph,
+                    );
+                }
+            }
+        }
+    }
+
     for (pos, render) in &data {
         let idx = map.xy_idx(pos.x, pos.y);
         if map.is_tile_status_set(idx, TileStatus::Visible) {
@@ -44,7 +78,7 @@ pub fn render(ecs: &World, ctx: &mut Rltk) {
                 ctx.set(
                     offset_x,
                     offset_y,
-                    render.colors.fg,
+                    render.colors.fg.to_rgb() * brightness(&map, idx),
                     render.colors.bg,
                     render.glyph,
                 );
@@ -57,7 +91,7 @@ fn get_tile_glyph(idx: usize, map: &Map) -> (rltk::FontCharType, ColorPair) {
     let bg = colors::BACKGROUND;
     #[allow(clippy::match_on_vec_items)]
     let (glyph, fg) = match map.tiles[idx] {
-        TileType::Wall => (
+        TileType::Wall | TileType::SecretDoor => (
             35,
             if map.is_tile_status_set(idx, TileStatus::Visible) {
                 colors::WALL_VISIBLE
@@ -65,13 +99,58 @@ fn get_tile_glyph(idx: usize, map: &Map) -> (rltk::FontCharType, ColorPair) {
                 colors::WALL_REVEALED
             },
         ),
+        TileType::Door => (43, colors::DOOR),
         TileType::Floor => (46, colors::FLOOR),
         TileType::StairsDown => (174, colors::STAIRS),
     };
 
+    let fg = if map.is_tile_status_set(idx, TileStatus::Visible) {
+        RGB::from(fg) * brightness(map, idx)
+    } else {
+        RGB::from(fg)
+    };
     (glyph, ColorPair::new(fg, bg))
 }
 
+///Visible tiles and what stands on them are shaded by how brightly they are lit
+fn brightness(map: &Map, idx: usize) -> f32 {
+    (1.0 - MIN_BRIGHTNESS).mul_add(map.light[idx], MIN_BRIGHTNESS)
+}
+
+///The map position under the mouse, if it is over the part of the map on screen
+pub fn mouse_to_map(ecs: &World, ctx: &Rltk) -> Option<Point> {
+    let (min_x, _, min_y, _) = get_screen_bounds(ecs);
+    let (mouse_x, mouse_y) = ctx.mouse_pos();
+    let on_screen = is_on_screen(ecs, Point::new(mouse_x, mouse_y));
+    let target = Point::new(mouse_x + min_x, mouse_y + min_y);
+
+    let map = ecs.fetch::<Map>();
+    let in_map = target.x >= 0 && target.x < map.width && target.y >= 0 && target.y < map.height;
+    (on_screen && in_map).then_some(target)
+}
+
+///Whether the screen position `screen` is on the part of the map console that `render` draws
+pub fn is_on_screen(ecs: &World, screen: Point) -> bool {
+    let (min_x, max_x, min_y, max_y) = get_screen_bounds(ecs);
+    screen.x >= EDGE_BUFFER as i32
+        && screen.x < max_x - min_x
+        && screen.y >= EDGE_BUFFER as i32
+        && screen.y < max_y - min_y
+}
+
+///The top left corner for a box of `width` by `height` drawn beside the screen position `screen`.
+///The box goes on whichever side of it has more room.
+pub fn place_beside(ecs: &World, screen: Point, width: i32, height: i32) -> Point {
+    let (min_x, max_x, min_y, max_y) = get_screen_bounds(ecs);
+    let x = if screen.x < (max_x - min_x) / 2 {
+        screen.x + 2
+    } else {
+        (screen.x - width - 2).max(0)
+    };
+    let y = screen.y.min(max_y - min_y - height).max(0);
+    Point::new(x, y)
+}
+
 pub fn get_screen_bounds(ecs: &World) -> (i32, i32, i32, i32) {
     let player_pos = ecs.fetch::<Point>();
     let (x_chars, y_chars) = (57, 43); //Determined by UI Image
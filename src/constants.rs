@@ -4,6 +4,7 @@
 
 pub mod colors {
     pub const BACKGROUND: (u8, u8, u8) = (17, 0, 22);
+    pub const DOOR: (u8, u8, u8) = (153, 102, 51);
     pub const FLOOR: (u8, u8, u8) = (26, 26, 26);
     pub const FOREGROUND: (u8, u8, u8) = (243, 251, 241);
     pub const STAIRS: (u8, u8, u8) = (0, 0, 255);
@@ -1,4 +1,4 @@
-use crate::map_builder::map::Map;
+use crate::{ecs::identification::Identities, map_builder::map::Map};
 use rltk::ColorPair;
 use serde::{Deserialize, Serialize};
 use specs::{
@@ -7,6 +7,7 @@ use specs::{
     saveload::{ConvertSaveload, Marker},
 };
 use specs_derive::*;
+use strum::{AsRefStr, EnumIter};
 
 //Components are organized by who they are **TYPICALLY** assigned to.
 //(N)PC
@@ -32,11 +33,18 @@ pub struct Render {
     pub render_order: i32,
 }
 
+///Lights up the tiles around it, or around its owner while equipped
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct LightSource {
+    pub range: i32,
+}
+
 pub struct SerializeMe;
 
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct SerializationHelper {
     pub map: Map,
+    pub identities: Identities,
 }
 
 //(N)PC Components
@@ -49,6 +57,16 @@ pub struct Player {}
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct Monster {}
 
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Faction {
+    pub name: String,
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct LootTable {
+    pub table: String,
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct CombatStats {
     pub max_hp: i32,
@@ -57,6 +75,32 @@ pub struct CombatStats {
     pub power: i32,
 }
 
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Corpse {
+    pub turns_left: i32,
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Regeneration {
+    pub amount: i32,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, AsRefStr)]
+pub enum HungerState {
+    #[strum(serialize = "Well Fed")]
+    WellFed,
+    Normal,
+    Hungry,
+    Starving,
+}
+
+///Counts down the turns until its owner moves to the next, hungrier state
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct HungerClock {
+    pub state: HungerState,
+    pub duration: i32,
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct SufferDamage {
     pub amount: Vec<i32>,
@@ -90,6 +134,7 @@ pub struct WantsToMelee {
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct WantsToDropItem {
     pub item: Entity,
+    pub quantity: i32,
 }
 
 #[derive(Component, Debug, ConvertSaveload, Clone)]
@@ -109,6 +154,10 @@ pub struct WantsToUseItem {
     pub target: Option<rltk::Point>,
 }
 
+///Carrying more than its capacity slows an entity down and weakens its attacks
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Overburdened {}
+
 //Item components
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct Item {}
@@ -116,6 +165,33 @@ pub struct Item {}
 #[derive(Component, Debug, Serialize, Deserialize, Clone)]
 pub struct Consumable {}
 
+///Items with charges are kept when used, until they run out
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Charges {
+    pub remaining: i32,
+    pub crumbles: bool,
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Weight {
+    pub weight: i32,
+}
+
+///Identical stackable items are merged into a single item when picked up
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Stackable {
+    pub quantity: i32,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Artifact {}
+
+///Cursed items can't be removed once equipped. The curse is revealed the first time it is worn.
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Cursed {
+    pub revealed: bool,
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct AreaOfEffect {
     pub radius: i32,
@@ -131,6 +207,36 @@ pub struct InflictsDamage {
     pub damage: i32,
 }
 
+///Eating the item keeps the eater well fed for `nutrition` turns
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct ProvidesFood {
+    pub nutrition: i32,
+}
+
+///Reveals the whole map to the player
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct MagicMapper {}
+
+///Moves every target to a random free spot on the map
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Teleports {}
+
+///Takes the player back to the surface
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct TownPortal {}
+
+///Lifts the curse from up to `count` of the items carried by the user
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct RemovesCurse {
+    pub count: i32,
+}
+
+///Identifies up to `count` of the items carried by the user
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Identifies {
+    pub count: i32,
+}
+
 #[derive(Component, Debug, ConvertSaveload, Clone)]
 pub struct ProvidesHealing {
     pub heal_amount: i32,
@@ -146,13 +252,15 @@ pub struct InBackpack {
     pub owner: Entity,
 }
 
-#[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize, EnumIter, AsRefStr)]
 pub enum EquipmentSlot {
     Head,
     Neck,
     Torso,
     Body,
+    #[strum(serialize = "Primary Hand")]
     PrimaryHand,
+    #[strum(serialize = "Off Hand")]
     OffHand,
     Feet,
     Finger,
@@ -179,6 +287,34 @@ pub struct DefenseBonus {
     pub bonus: i32,
 }
 
+//Trap components
+///Springs on whoever steps onto it, using the effects of the trap entity like `InflictsDamage`
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Trap {
+    ///The chance in percent that the player spots the trap each time it comes into view
+    pub spot_chance: i32,
+    pub single_use: bool,
+}
+
+///Hidden entities aren't drawn until the player spots them
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Hidden {}
+
+///Holds its victim in place for `turns` turns
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Immobilizes {
+    pub turns: i32,
+}
+
+#[derive(Component, Debug, ConvertSaveload, Clone)]
+pub struct Immobilized {
+    pub turns: i32,
+}
+
+///Marks an entity that moved onto a new tile this turn, which may spring a trap
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct EntityMoved {}
+
 //Particles
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct ParticleLifetime {
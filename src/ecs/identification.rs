@@ -0,0 +1,124 @@
+use crate::{
+    components::Name,
+    raws::spawn::{Unidentified, SPAWN_RAWS},
+};
+use rltk::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+const POTION_DESCRIPTIONS: [&str; 8] = [
+    "bubbling",
+    "murky",
+    "fizzing",
+    "glowing",
+    "smoky",
+    "viscous",
+    "shimmering",
+    "cloudy",
+];
+const POTION_COLORS: [&str; 8] = [
+    "red", "blue", "green", "amber", "violet", "black", "silver", "pink",
+];
+const SCROLL_SYLLABLES: [&str; 12] = [
+    "ka", "zu", "mor", "vel", "thi", "oth", "ra", "nex", "bu", "qua", "ish", "tor",
+];
+
+///Keeps track of the obfuscated names of potions and scrolls for the current run, and which of
+///them the player has identified
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Identities {
+    obfuscated: HashMap<String, String>,
+    identified: HashSet<String>,
+}
+
+impl Identities {
+    ///Gives every unidentified item in the raws a new random name
+    pub fn new_run() -> Self {
+        let mut rng = RandomNumberGenerator::new();
+        let mut obfuscated = HashMap::new();
+        let mut used_names = HashSet::new();
+
+        let unidentified_items = SPAWN_RAWS.lock().unwrap().unidentified_items();
+        for (item, kind) in unidentified_items {
+            let mut name = random_name(&mut rng, kind);
+            while used_names.contains(&name) {
+                name = random_name(&mut rng, kind);
+            }
+            used_names.insert(name.clone());
+            obfuscated.insert(item, name);
+        }
+
+        Self {
+            obfuscated,
+            identified: HashSet::new(),
+        }
+    }
+
+    ///The name the player knows the item by
+    pub fn name_of<'a>(&'a self, name: &'a Name) -> &'a str {
+        match self.obfuscated.get(&name.name) {
+            Some(obfuscated) if !self.is_identified(name) => obfuscated,
+            _ => &name.name,
+        }
+    }
+
+    pub fn is_identified(&self, name: &Name) -> bool {
+        !self.obfuscated.contains_key(&name.name) || self.identified.contains(&name.name)
+    }
+
+    ///Returns true if the item was not identified before
+    pub fn identify(&mut self, name: &Name) -> bool {
+        !self.is_identified(name) && self.identified.insert(name.name.clone())
+    }
+}
+
+fn random_name(rng: &mut RandomNumberGenerator, kind: Unidentified) -> String {
+    let mut pick =
+        |words: &[&'static str]| words[rng.roll_dice(1, words.len() as i32) as usize - 1];
+    match kind {
+        Unidentified::Potion => format!(
+            "{} {} potion",
+            pick(&POTION_DESCRIPTIONS),
+            pick(&POTION_COLORS)
+        ),
+        Unidentified::Scroll => {
+            let label = (0..3).map(|_| pick(&SCROLL_SYLLABLES)).collect::<String>();
+            format!("scroll labeled \"{}\"", label.to_uppercase())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unidentified_items_get_distinct_names_until_identified() {
+        crate::raws::spawn::load().unwrap();
+        let mut identities = Identities::new_run();
+        let items = SPAWN_RAWS.lock().unwrap().unidentified_items();
+        assert!(!items.is_empty());
+
+        let mut seen = HashSet::new();
+        for (item, _) in &items {
+            let name = Name { name: item.clone() };
+            let shown = identities.name_of(&name).to_string();
+            assert_ne!(&shown, item);
+            assert!(seen.insert(shown));
+
+            assert!(identities.identify(&name));
+            assert!(!identities.identify(&name));
+            assert_eq!(identities.name_of(&name), item);
+        }
+    }
+
+    #[test]
+    fn ordinary_items_keep_their_name() {
+        let identities = Identities::default();
+        let dagger = Name {
+            name: "Dagger".to_string(),
+        };
+        assert!(identities.is_identified(&dagger));
+        assert_eq!(identities.name_of(&dagger), "Dagger");
+    }
+}
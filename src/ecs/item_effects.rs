@@ -0,0 +1,534 @@
+use crate::{
+    components::{
+        AreaOfEffect, CombatStats, Cursed, Equipped, FieldOfView, HungerClock, HungerState,
+        Identifies, InBackpack, InflictsDamage, MagicMapper, Name, Position, ProvidesFood,
+        ProvidesHealing, Range, RemovesCurse, SufferDamage, Teleports, TownPortal,
+    },
+    ecs::identification::Identities,
+    game_log::GameLog,
+    map_builder::map::{Map, TileStatus, TileType},
+    state::{Gameplay, State},
+};
+use rltk::{Point, RandomNumberGenerator};
+use specs::{prelude::*, EntityBuilder};
+use std::{fmt, str::FromStr};
+
+lazy_static::lazy_static! {
+    pub static ref EFFECTS: EffectRegistry = EffectRegistry::new();
+}
+
+///Everything an effect needs to know about a single use of an item
+pub struct EffectUse {
+    pub user: Entity,
+    pub item: Entity,
+    pub targets: Vec<Entity>,
+}
+
+///A component that can be given to an item through the `effects` of a raw consumable
+pub trait ItemEffect: Component + Clone + Send + Sync {
+    ///The key used for the effect in the raws
+    const NAME: &'static str;
+    type Param: FromStr;
+
+    fn from_param(param: Self::Param) -> Self;
+
+    ///Called when an item with this effect is used. Effects that only modify how an item is used,
+    ///like `Range`, have nothing to do here.
+    fn apply(&self, _ecs: &World, _usage: &EffectUse) {}
+}
+
+///The value of effects that take no parameter, which is written as "true" in the raws
+pub struct Flag;
+
+impl FromStr for Flag {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "true" {
+            Ok(Self)
+        } else {
+            Err(())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EffectError {
+    pub item: String,
+    pub field: String,
+    pub reason: String,
+}
+
+impl fmt::Display for EffectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Item \"{}\": effect \"{}\" {}",
+            self.item, self.field, self.reason
+        )
+    }
+}
+
+type ValidateFn = fn(&str) -> Result<(), String>;
+type BuildFn = for<'a> fn(EntityBuilder<'a>, &str) -> Result<EntityBuilder<'a>, String>;
+type ApplyFn = fn(&World, &EffectUse);
+
+struct RegisteredEffect {
+    name: &'static str,
+    validate: ValidateFn,
+    build: BuildFn,
+    apply: ApplyFn,
+}
+
+pub struct EffectRegistry {
+    effects: Vec<RegisteredEffect>,
+}
+
+impl EffectRegistry {
+    ///All effects known to the game. Adding an effect only requires registering it here.
+    fn new() -> Self {
+        let mut registry = Self {
+            effects: Vec::new(),
+        };
+        registry.register::<ProvidesHealing>();
+        registry.register::<ProvidesFood>();
+        registry.register::<InflictsDamage>();
+        registry.register::<Range>();
+        registry.register::<AreaOfEffect>();
+        registry.register::<Identifies>();
+        registry.register::<RemovesCurse>();
+        registry.register::<MagicMapper>();
+        registry.register::<Teleports>();
+        registry.register::<TownPortal>();
+        registry
+    }
+
+    fn register<E: ItemEffect>(&mut self) {
+        self.effects.push(RegisteredEffect {
+            name: E::NAME,
+            validate: |value| parse_effect::<E>(value).map(|_| ()),
+            build: build_effect::<E>,
+            apply: apply_effect::<E>,
+        });
+    }
+
+    ///Checks that every effect is known, and that its value is valid
+    pub fn validate<'a, I>(&self, item: &str, effects: I) -> Result<(), EffectError>
+    where
+        I: IntoIterator<Item = (&'a String, &'a String)>,
+    {
+        for (field, value) in effects {
+            let effect = self.find(item, field)?;
+            (effect.validate)(value).map_err(|reason| EffectError {
+                item: item.to_string(),
+                field: field.clone(),
+                reason,
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn build<'a>(
+        &self,
+        new_entity: EntityBuilder<'a>,
+        item: &str,
+        field: &str,
+        value: &str,
+    ) -> Result<EntityBuilder<'a>, EffectError> {
+        let effect = self.find(item, field)?;
+        (effect.build)(new_entity, value).map_err(|reason| EffectError {
+            item: item.to_string(),
+            field: field.to_string(),
+            reason,
+        })
+    }
+
+    ///Applies every effect the used item has
+    pub fn apply(&self, ecs: &World, usage: &EffectUse) {
+        for effect in &self.effects {
+            (effect.apply)(ecs, usage);
+        }
+    }
+
+    fn find(&self, item: &str, field: &str) -> Result<&RegisteredEffect, EffectError> {
+        self.effects
+            .iter()
+            .find(|effect| effect.name == field)
+            .ok_or_else(|| EffectError {
+                item: item.to_string(),
+                field: field.to_string(),
+                reason: "is not a known effect".to_string(),
+            })
+    }
+}
+
+fn parse_effect<E: ItemEffect>(value: &str) -> Result<E, String> {
+    value.parse::<E::Param>().map(E::from_param).map_err(|_| {
+        format!(
+            "expects a value of type {}, but found \"{}\"",
+            std::any::type_name::<E::Param>(),
+            value
+        )
+    })
+}
+
+fn build_effect<'a, E: ItemEffect>(
+    new_entity: EntityBuilder<'a>,
+    value: &str,
+) -> Result<EntityBuilder<'a>, String> {
+    parse_effect::<E>(value).map(|effect| new_entity.with(effect))
+}
+
+///Every item in the backpack of, or equipped by the user, apart from the item being used
+fn carried_items(ecs: &World, usage: &EffectUse) -> Vec<Entity> {
+    let entities = ecs.entities();
+    let backpack = ecs.read_storage::<InBackpack>();
+    let equipped_items = ecs.read_storage::<Equipped>();
+
+    entities
+        .join()
+        .filter(|item| {
+            *item != usage.item
+                && (backpack
+                    .get(*item)
+                    .is_some_and(|pack| pack.owner == usage.user)
+                    || equipped_items
+                        .get(*item)
+                        .is_some_and(|equipped| equipped.owner == usage.user))
+        })
+        .collect()
+}
+
+fn apply_effect<E: ItemEffect>(ecs: &World, usage: &EffectUse) {
+    let effect = ecs.read_storage::<E>().get(usage.item).cloned();
+    if let Some(effect) = effect {
+        effect.apply(ecs, usage);
+    }
+}
+
+impl ItemEffect for ProvidesHealing {
+    const NAME: &'static str = "provides_healing";
+    type Param = i32;
+
+    fn from_param(heal_amount: i32) -> Self {
+        Self { heal_amount }
+    }
+
+    fn apply(&self, ecs: &World, usage: &EffectUse) {
+        let player_ent = ecs.fetch::<Entity>();
+        let identities = ecs.fetch::<Identities>();
+        let names = ecs.read_storage::<Name>();
+        let mut all_stats = ecs.write_storage::<CombatStats>();
+        let mut logs = ecs.fetch_mut::<GameLog>();
+
+        for target in &usage.targets {
+            if let Some(stats) = all_stats.get_mut(*target) {
+                stats.hp = i32::min(stats.max_hp, stats.hp + self.heal_amount);
+                let item_name = identities.name_of(names.get(usage.item).unwrap());
+                if usage.user == *player_ent {
+                    logs.push(&format!(
+                        "You use the {}, healing {} hp.",
+                        item_name, self.heal_amount
+                    ));
+                } else if let Some(user_name) = names.get(usage.user) {
+                    logs.push(&format!(
+                        "{} uses the {}, healing {} hp.",
+                        user_name.name, item_name, self.heal_amount
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl ItemEffect for ProvidesFood {
+    const NAME: &'static str = "food";
+    type Param = i32;
+
+    fn from_param(nutrition: i32) -> Self {
+        Self { nutrition }
+    }
+
+    fn apply(&self, ecs: &World, usage: &EffectUse) {
+        let player_ent = ecs.fetch::<Entity>();
+        let identities = ecs.fetch::<Identities>();
+        let names = ecs.read_storage::<Name>();
+        let mut clocks = ecs.write_storage::<HungerClock>();
+        let mut logs = ecs.fetch_mut::<GameLog>();
+
+        for target in &usage.targets {
+            if let Some(clock) = clocks.get_mut(*target) {
+                clock.state = HungerState::WellFed;
+                clock.duration = self.nutrition;
+                if *target == *player_ent {
+                    logs.push(&format!(
+                        "You eat the {}.",
+                        identities.name_of(names.get(usage.item).unwrap())
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl ItemEffect for InflictsDamage {
+    const NAME: &'static str = "damage";
+    type Param = i32;
+
+    fn from_param(damage: i32) -> Self {
+        Self { damage }
+    }
+
+    fn apply(&self, ecs: &World, usage: &EffectUse) {
+        let player_ent = ecs.fetch::<Entity>();
+        let identities = ecs.fetch::<Identities>();
+        let names = ecs.read_storage::<Name>();
+        let all_stats = ecs.read_storage::<CombatStats>();
+        let mut suffering = ecs.write_storage::<SufferDamage>();
+        let mut logs = ecs.fetch_mut::<GameLog>();
+
+        for mob in &usage.targets {
+            SufferDamage::new_damage(&mut suffering, *mob, self.damage);
+            if all_stats.get(*mob).is_some() {
+                let mob_name = &names.get(*mob).unwrap().name;
+                let item_name = identities.name_of(names.get(usage.item).unwrap());
+                if usage.user == *player_ent {
+                    logs.push(&format!(
+                        "You use {} on {} inflicting {} damage.",
+                        item_name, mob_name, self.damage
+                    ));
+                } else if let Some(user_name) = names.get(usage.user) {
+                    logs.push(&format!(
+                        "{} uses {} on {} inflicting {} damage.",
+                        user_name.name, item_name, mob_name, self.damage
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl ItemEffect for Range {
+    const NAME: &'static str = "range";
+    type Param = i32;
+
+    fn from_param(range: i32) -> Self {
+        Self { range }
+    }
+}
+
+impl ItemEffect for AreaOfEffect {
+    const NAME: &'static str = "area_of_effect";
+    type Param = i32;
+
+    fn from_param(radius: i32) -> Self {
+        Self { radius }
+    }
+}
+
+impl ItemEffect for Identifies {
+    const NAME: &'static str = "identify";
+    type Param = i32;
+
+    fn from_param(count: i32) -> Self {
+        Self { count }
+    }
+
+    fn apply(&self, ecs: &World, usage: &EffectUse) {
+        if usage.user != *ecs.fetch::<Entity>() {
+            return;
+        }
+
+        let names = ecs.read_storage::<Name>();
+        let mut identities = ecs.fetch_mut::<Identities>();
+        let mut logs = ecs.fetch_mut::<GameLog>();
+
+        let mut remaining = self.count;
+        for name in carried_items(ecs, usage)
+            .iter()
+            .filter_map(|item| names.get(*item))
+        {
+            if remaining <= 0 {
+                break;
+            }
+            let unknown_name = identities.name_of(name).to_string();
+            if identities.identify(name) {
+                logs.push(&format!("The {} is a {}.", unknown_name, name.name));
+                remaining -= 1;
+            }
+        }
+
+        if remaining == self.count {
+            logs.push(&"You have nothing left to identify.");
+        }
+    }
+}
+
+impl ItemEffect for RemovesCurse {
+    const NAME: &'static str = "remove_curse";
+    type Param = i32;
+
+    fn from_param(count: i32) -> Self {
+        Self { count }
+    }
+
+    ///Worn items are freed first, since those are the curses that keep the user stuck with them
+    fn apply(&self, ecs: &World, usage: &EffectUse) {
+        let player_ent = ecs.fetch::<Entity>();
+        let names = ecs.read_storage::<Name>();
+        let equipped_items = ecs.read_storage::<Equipped>();
+        let identities = ecs.fetch::<Identities>();
+        let mut cursed = ecs.write_storage::<Cursed>();
+        let mut logs = ecs.fetch_mut::<GameLog>();
+
+        let mut cursed_items = carried_items(ecs, usage)
+            .into_iter()
+            .filter(|item| cursed.get(*item).is_some())
+            .collect::<Vec<_>>();
+        cursed_items.sort_by_key(|item| equipped_items.get(*item).is_none());
+
+        let lifted = cursed_items.len().min(self.count.max(0) as usize);
+        for item in cursed_items.into_iter().take(lifted) {
+            cursed.remove(item);
+            if usage.user == *player_ent {
+                logs.push(&format!(
+                    "The curse on the {} is lifted.",
+                    identities.name_of(names.get(item).unwrap())
+                ));
+            }
+        }
+
+        if lifted == 0 && usage.user == *player_ent {
+            logs.push(&"You feel as if someone is watching over you.");
+        }
+    }
+}
+
+impl ItemEffect for MagicMapper {
+    const NAME: &'static str = "magic_mapping";
+    type Param = Flag;
+
+    fn from_param(_: Flag) -> Self {
+        Self {}
+    }
+
+    ///The map is revealed row by row while in `Gameplay::RevealMap`
+    fn apply(&self, ecs: &World, usage: &EffectUse) {
+        if usage.user != *ecs.fetch::<Entity>() {
+            return;
+        }
+
+        ecs.fetch_mut::<GameLog>()
+            .push(&"The layout of the level unfolds in your mind!");
+        *ecs.fetch_mut::<State>() = State::Game(Gameplay::RevealMap(0));
+    }
+}
+
+impl ItemEffect for Teleports {
+    const NAME: &'static str = "teleport";
+    type Param = Flag;
+
+    fn from_param(_: Flag) -> Self {
+        Self {}
+    }
+
+    fn apply(&self, ecs: &World, usage: &EffectUse) {
+        let player_ent = ecs.fetch::<Entity>();
+        let names = ecs.read_storage::<Name>();
+        let mut map = ecs.fetch_mut::<Map>();
+        let mut positions = ecs.write_storage::<Position>();
+        let mut fields_of_view = ecs.write_storage::<FieldOfView>();
+        let mut logs = ecs.fetch_mut::<GameLog>();
+        let mut rng = RandomNumberGenerator::new();
+
+        for target in &usage.targets {
+            let free_tiles = (0..map.tiles.len())
+                .filter(|idx| {
+                    map.tiles[*idx] == TileType::Floor
+                        && !map.is_tile_status_set(*idx, TileStatus::Blocked)
+                })
+                .collect::<Vec<_>>();
+            let (Some(pos), false) = (positions.get_mut(*target), free_tiles.is_empty()) else {
+                continue;
+            };
+
+            let destination = free_tiles[rng.random_slice_index(&free_tiles).unwrap()];
+            let old_idx = map.xy_idx(pos.x, pos.y);
+            map.remove_tile_status(old_idx, TileStatus::Blocked);
+            map.set_tile_status(destination, TileStatus::Blocked);
+            pos.x = destination as i32 % map.width;
+            pos.y = destination as i32 / map.width;
+            if let Some(fov) = fields_of_view.get_mut(*target) {
+                fov.is_dirty = true;
+            }
+
+            if *target == *player_ent {
+                *ecs.write_resource::<Point>() = Point::new(pos.x, pos.y);
+                logs.push(&"The world twists around you, and you find yourself elsewhere.");
+            } else if let Some(name) = names.get(*target) {
+                logs.push(&format!("{} vanishes!", name.name));
+            }
+        }
+    }
+}
+
+impl ItemEffect for TownPortal {
+    const NAME: &'static str = "town_portal";
+    type Param = Flag;
+
+    fn from_param(_: Flag) -> Self {
+        Self {}
+    }
+
+    ///The level is changed while in `Gameplay::TownPortal`
+    fn apply(&self, ecs: &World, usage: &EffectUse) {
+        if usage.user != *ecs.fetch::<Entity>() {
+            return;
+        }
+
+        if ecs.fetch::<Map>().depth <= 1 {
+            ecs.fetch_mut::<GameLog>()
+                .push(&"A portal flickers open, but you are already on the surface.");
+        } else {
+            *ecs.fetch_mut::<State>() = State::Game(Gameplay::TownPortal);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    ///Effects are written the same way as in the `effects` map of an item in spawns.ron
+    fn validate(effects: &str) -> Result<(), EffectError> {
+        let effects: HashMap<String, String> = ron::de::from_str(effects).unwrap();
+        EffectRegistry::new().validate("Test Item", &effects)
+    }
+
+    #[test]
+    fn known_effects_with_valid_values_pass() {
+        assert!(validate(r#"{"provides_healing": "8", "magic_mapping": "true"}"#).is_ok());
+    }
+
+    #[test]
+    fn errors_name_the_item_and_the_effect() {
+        let error = validate(r#"{"levitation": "true"}"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Item \"Test Item\": effect \"levitation\" is not a known effect"
+        );
+    }
+
+    #[test]
+    fn values_of_the_wrong_type_are_rejected() {
+        assert_eq!(
+            validate(r#"{"damage": "lots"}"#).unwrap_err().field,
+            "damage"
+        );
+        assert_eq!(
+            validate(r#"{"teleport": "false"}"#).unwrap_err().field,
+            "teleport"
+        );
+    }
+}
@@ -1,19 +1,30 @@
 pub mod components;
+pub mod identification;
+pub mod item_effects;
 mod systems;
 pub use components::*;
+pub use systems::carried_weight;
+pub use systems::carry_capacity;
 pub use systems::cull_dead_characters;
 pub use systems::cull_dead_particles;
+pub use systems::discover_secret_door;
+pub use systems::is_out_of_charges;
 pub use systems::ParticleBuilder;
+pub use systems::WELL_FED_DURATION;
 
 pub mod pre_run_systems {
     use crate::ecs::systems;
     use specs::{RunNow, WorldExt};
 
     pub fn execute(world: &mut specs::World) {
+        let mut lighting = systems::LightingSystem {};
         let mut vis = systems::VisibilitySystem {};
+        let mut memory = systems::MemorySystem {};
         let mut map_index = systems::MapIndexingSystem {};
 
+        lighting.run_now(world);
         vis.run_now(world);
+        memory.run_now(world);
         map_index.run_now(world);
 
         world.maintain();
@@ -25,27 +36,39 @@ pub mod all_systems {
     use specs::{RunNow, WorldExt};
 
     pub fn execute(world: &mut specs::World) {
+        let mut lighting = systems::LightingSystem {};
         let mut vis = systems::VisibilitySystem {};
+        let mut memory = systems::MemorySystem {};
         let mut map_index = systems::MapIndexingSystem {};
         let mut mons = systems::MonsterAI {};
         let mut melee = systems::MeleeCombatSystem {};
         let mut damage = systems::DamageSystem {};
+        let mut regen = systems::RegenerationSystem {};
+        let mut hunger = systems::HungerSystem {};
         let mut pickup_items = systems::ItemCollectionSystem {};
-        let mut use_items = systems::ItemUseSystem {};
         let mut drop_items = systems::ItemDropSystem {};
         let mut rem_items = systems::ItemRemoveSystem {};
+        let mut encumbrance = systems::EncumbranceSystem {};
         let mut particles = systems::ParticleSpawnSystem {};
+        let mut corpses = systems::CorpseDecaySystem {};
 
+        lighting.run_now(world);
         vis.run_now(world);
+        memory.run_now(world);
         mons.run_now(world);
         map_index.run_now(world);
+        systems::trigger_traps(world);
         melee.run_now(world);
         damage.run_now(world);
+        regen.run_now(world);
+        hunger.run_now(world);
         pickup_items.run_now(world);
-        use_items.run_now(world);
+        systems::use_items(world);
         drop_items.run_now(world);
         rem_items.run_now(world);
+        encumbrance.run_now(world);
         particles.run_now(world);
+        corpses.run_now(world);
 
         world.maintain();
     }
@@ -0,0 +1,33 @@
+use crate::{
+    components::Corpse,
+    state::{Gameplay, State, State::Game},
+};
+use specs::prelude::*;
+
+pub struct CorpseDecaySystem {}
+
+impl<'a> System<'a> for CorpseDecaySystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, State>,
+        WriteStorage<'a, Corpse>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, state, mut corpses) = data;
+
+        //Corpses only decay once per round
+        if *state != Game(Gameplay::MonsterTurn) {
+            return;
+        }
+
+        for (ent, corpse) in (&entities, &mut corpses).join() {
+            corpse.turns_left -= 1;
+            if corpse.turns_left < 1 {
+                entities
+                    .delete(ent)
+                    .expect("Unable to delete decayed corpse");
+            }
+        }
+    }
+}
@@ -1,6 +1,18 @@
+use crate::raws::spawn::{SpawnType, SPAWN_RAWS};
 use crate::state::{Gameplay, State};
-use crate::{CombatStats, GameLog, Name, Player, State::Game, SufferDamage};
-use specs::prelude::*;
+use crate::{
+    CombatStats, Corpse, Equipped, GameLog, InBackpack, LootTable, Name, Player, Position, Render,
+    SerializeMe, State::Game, SufferDamage,
+};
+use rltk::ColorPair;
+use specs::{
+    prelude::*,
+    saveload::{MarkedBuilder, SimpleMarker},
+};
+
+const CORPSE_DECAY_TURNS: i32 = 40;
+//Render order is drawn from highest to lowest, so corpses lie beneath everything else
+const CORPSE_RENDER_ORDER: i32 = 3;
 
 pub struct DamageSystem {}
 
@@ -48,7 +60,102 @@ pub fn cull_dead_characters(ecs: &mut World) {
             }
         }
     }
+    for victim in &dead {
+        leave_corpse(ecs, *victim);
+        drop_loot(ecs, *victim);
+    }
     for victim in dead {
         ecs.delete_entity(victim).expect("Unable to delete victim");
     }
 }
+
+///Leaves behind a darkened copy of the victim that decays over time
+fn leave_corpse(ecs: &mut World, victim: Entity) {
+    let remains = {
+        let positions = ecs.read_storage::<Position>();
+        let renders = ecs.read_storage::<Render>();
+        let names = ecs.read_storage::<Name>();
+        match (
+            positions.get(victim),
+            renders.get(victim),
+            names.get(victim),
+        ) {
+            (Some(pos), Some(render), Some(name)) => Some((
+                pos.clone(),
+                Render {
+                    glyph: render.glyph,
+                    colors: ColorPair::new(render.colors.fg * 0.5, render.colors.bg),
+                    render_order: CORPSE_RENDER_ORDER,
+                },
+                Name {
+                    name: format!("{} corpse", name.name),
+                },
+            )),
+            _ => None,
+        }
+    };
+
+    if let Some((pos, render, name)) = remains {
+        ecs.create_entity()
+            .with(pos)
+            .with(render)
+            .with(name)
+            .with(Corpse {
+                turns_left: CORPSE_DECAY_TURNS,
+            })
+            .marked::<SimpleMarker<SerializeMe>>()
+            .build();
+    }
+}
+
+///Places everything the victim carried, as well as a roll on its loot table, where it died
+fn drop_loot(ecs: &mut World, victim: Entity) {
+    let victim_pos = match ecs.read_storage::<Position>().get(victim) {
+        Some(pos) => pos.clone(),
+        None => return,
+    };
+
+    //Carried items are dropped
+    {
+        let entities = ecs.entities();
+        let mut backpack = ecs.write_storage::<InBackpack>();
+        let mut equipped_items = ecs.write_storage::<Equipped>();
+        let mut positions = ecs.write_storage::<Position>();
+
+        let carried_items = (&entities, backpack.mask() | equipped_items.mask())
+            .join()
+            .map(|(item, _)| item)
+            .filter(|item| {
+                backpack.get(*item).is_some_and(|pack| pack.owner == victim)
+                    || equipped_items
+                        .get(*item)
+                        .is_some_and(|eq| eq.owner == victim)
+            })
+            .collect::<Vec<_>>();
+
+        for item in carried_items {
+            backpack.remove(item);
+            equipped_items.remove(item);
+            positions
+                .insert(item, victim_pos.clone())
+                .expect("Unable to drop item carried by victim");
+        }
+    }
+
+    //Roll for any additional loot
+    let loot_table = ecs
+        .read_storage::<LootTable>()
+        .get(victim)
+        .map(|loot| loot.table.clone());
+    if let Some(loot_table) = loot_table {
+        let mut rng = rltk::RandomNumberGenerator::new();
+        let spawn_raws = SPAWN_RAWS.lock().unwrap();
+        if let Some(drop) = spawn_raws.roll_loot(&loot_table, &mut rng) {
+            spawn_raws.spawn_named_entity(
+                ecs.create_entity(),
+                &drop,
+                SpawnType::AtPosition(victim_pos.x, victim_pos.y),
+            );
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{
+    components::{CombatStats, Equipped, InBackpack, Overburdened, Stackable, Weight},
+    game_log::GameLog,
+};
+use specs::prelude::*;
+
+const BASE_CARRY_CAPACITY: i32 = 10;
+const CARRY_CAPACITY_PER_POWER: i32 = 4;
+
+///How much an entity can carry before becoming overburdened
+pub const fn carry_capacity(stats: &CombatStats) -> i32 {
+    BASE_CARRY_CAPACITY + stats.power * CARRY_CAPACITY_PER_POWER
+}
+
+///The total weight of everything in the backpack of, or equipped by `owner`
+pub fn carried_weight(
+    owner: Entity,
+    weights: &ReadStorage<'_, Weight>,
+    stackables: &ReadStorage<'_, Stackable>,
+    backpack: &ReadStorage<'_, InBackpack>,
+    equipped_items: &ReadStorage<'_, Equipped>,
+) -> i32 {
+    let carried = (backpack, weights, stackables.maybe())
+        .join()
+        .filter(|(pack, _, _)| pack.owner == owner)
+        .map(|(_, weight, stack)| weight.weight * stack.map_or(1, |stack| stack.quantity))
+        .sum::<i32>();
+    let worn = (equipped_items, weights)
+        .join()
+        .filter(|(equipped, _)| equipped.owner == owner)
+        .map(|(_, weight)| weight.weight)
+        .sum::<i32>();
+
+    carried + worn
+}
+
+pub struct EncumbranceSystem {}
+
+impl<'a> System<'a> for EncumbranceSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Stackable>,
+        ReadStorage<'a, Weight>,
+        WriteExpect<'a, GameLog>,
+        WriteStorage<'a, Overburdened>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_ent,
+            all_stats,
+            equipped_items,
+            backpack,
+            stackables,
+            weights,
+            mut logs,
+            mut overburdened,
+        ) = data;
+
+        for (ent, stats) in (&entities, &all_stats).join() {
+            let weight = carried_weight(ent, &weights, &stackables, &backpack, &equipped_items);
+            let is_overburdened = weight > carry_capacity(stats);
+            let was_overburdened = overburdened.get(ent).is_some();
+
+            if is_overburdened && !was_overburdened {
+                overburdened
+                    .insert(ent, Overburdened {})
+                    .expect("Unable to insert overburdened");
+                if ent == *player_ent {
+                    logs.push(&"You are overburdened, and struggle to move and fight.");
+                }
+            } else if !is_overburdened && was_overburdened {
+                overburdened.remove(ent);
+                if ent == *player_ent {
+                    logs.push(&"You are no longer overburdened.");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::EquipmentSlot;
+
+    #[test]
+    fn stacks_and_worn_items_count_towards_being_overburdened() {
+        let mut world = World::new();
+        world.register::<CombatStats>();
+        world.register::<Equipped>();
+        world.register::<InBackpack>();
+        world.register::<Overburdened>();
+        world.register::<Stackable>();
+        world.register::<Weight>();
+        world.insert(GameLog::new());
+
+        let stats = CombatStats {
+            max_hp: 10,
+            hp: 10,
+            defense: 0,
+            power: 0,
+        };
+        let player = world.create_entity().with(stats.clone()).build();
+        let other = world.create_entity().with(stats).build();
+        world.insert(player);
+        world
+            .create_entity()
+            .with(Weight { weight: 2 })
+            .with(Stackable { quantity: 4 })
+            .with(InBackpack { owner: player })
+            .build();
+        world
+            .create_entity()
+            .with(Weight { weight: 50 })
+            .with(InBackpack { owner: other })
+            .build();
+        let armor = world
+            .create_entity()
+            .with(Weight { weight: 3 })
+            .with(Equipped {
+                owner: player,
+                slot: EquipmentSlot::Torso,
+            })
+            .build();
+
+        EncumbranceSystem {}.run_now(&world);
+        assert_eq!(
+            BASE_CARRY_CAPACITY,
+            carry_capacity(world.read_storage::<CombatStats>().get(player).unwrap())
+        );
+        assert!(world.read_storage::<Overburdened>().get(player).is_some());
+        assert!(world.read_storage::<Overburdened>().get(other).is_some());
+
+        world.write_storage::<Equipped>().remove(armor);
+        EncumbranceSystem {}.run_now(&world);
+        assert!(world.read_storage::<Overburdened>().get(player).is_none());
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    components::{HungerClock, HungerState, SufferDamage},
+    game_log::GameLog,
+    state::{Gameplay, State, State::Game},
+};
+use specs::prelude::*;
+
+///The player sets out as well fed as a ration would leave them
+pub const WELL_FED_DURATION: i32 = 150;
+const NORMAL_DURATION: i32 = 300;
+const HUNGRY_DURATION: i32 = 150;
+const STARVATION_DAMAGE: i32 = 1;
+
+pub struct HungerSystem {}
+
+impl<'a> System<'a> for HungerSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, State>,
+        WriteExpect<'a, GameLog>,
+        WriteStorage<'a, HungerClock>,
+        WriteStorage<'a, SufferDamage>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, player_ent, state, mut logs, mut clocks, mut suffering) = data;
+
+        //The clock only ticks once for every turn of the player
+        if *state != Game(Gameplay::PlayerTurn) {
+            return;
+        }
+
+        for (ent, clock) in (&entities, &mut clocks).join() {
+            clock.duration -= 1;
+            if clock.duration > 0 {
+                continue;
+            }
+
+            let message = match clock.state {
+                HungerState::WellFed => {
+                    clock.state = HungerState::Normal;
+                    clock.duration = NORMAL_DURATION;
+                    Some("You are no longer well fed.")
+                }
+                HungerState::Normal => {
+                    clock.state = HungerState::Hungry;
+                    clock.duration = HUNGRY_DURATION;
+                    Some("You are hungry.")
+                }
+                HungerState::Hungry => {
+                    clock.state = HungerState::Starving;
+                    Some("You are starving!")
+                }
+                HungerState::Starving => {
+                    //The clock has run out, so starvation hurts every turn until the owner eats
+                    SufferDamage::new_damage(&mut suffering, ent, STARVATION_DAMAGE);
+                    clock.duration = 0;
+                    None
+                }
+            };
+            if let (Some(message), true) = (message, ent == *player_ent) {
+                logs.push(&message);
+            }
+        }
+    }
+}
@@ -1,63 +1,97 @@
 use crate::{
     components::{
-        AreaOfEffect, CombatStats, Consumable, Equipment, Equipped, InBackpack, InflictsDamage,
-        Name, Position, ProvidesHealing, SufferDamage, WantsToDropItem, WantsToPickupItem,
-        WantsToRemoveItem, WantsToUseItem,
+        AreaOfEffect, Artifact, Charges, Consumable, Cursed, Equipment, Equipped, InBackpack, Name,
+        Position, Stackable, WantsToDropItem, WantsToPickupItem, WantsToRemoveItem, WantsToUseItem,
+    },
+    ecs::{
+        identification::Identities,
+        item_effects::{EffectUse, EFFECTS},
     },
     game_log::GameLog,
     map_builder::map::Map,
+    specs_helpers,
+    state::{Gameplay, State, State::Game},
 };
 use rltk::{Algorithm2D, Point};
-use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
-
-const INVENTORY_LIMIT: usize = 9;
+use specs::{
+    Entities, Entity, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, World, WorldExt,
+    WriteExpect, WriteStorage,
+};
 
 pub struct ItemCollectionSystem {}
 
 impl<'a> System<'a> for ItemCollectionSystem {
     #[allow(clippy::type_complexity)]
     type SystemData = (
+        Entities<'a>,
         ReadExpect<'a, Entity>,
+        ReadExpect<'a, Identities>,
+        ReadStorage<'a, Artifact>,
         ReadStorage<'a, Name>,
         WriteExpect<'a, GameLog>,
+        WriteExpect<'a, State>,
         WriteStorage<'a, InBackpack>,
         WriteStorage<'a, Position>,
+        WriteStorage<'a, Stackable>,
         WriteStorage<'a, WantsToPickupItem>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (player_ent, names, mut logs, mut backpack, mut positions, mut attempts) = data;
-
-        let player_inventory_size = (&backpack)
-            .join()
-            .filter(|&x| x.owner == *player_ent)
-            .count();
+        let (
+            entities,
+            player_ent,
+            identities,
+            artifacts,
+            names,
+            mut logs,
+            mut state,
+            mut backpack,
+            mut positions,
+            mut stackables,
+            mut attempts,
+        ) = data;
 
         for pickup in attempts.join() {
-            if player_inventory_size >= INVENTORY_LIMIT {
-                logs.push(&format!(
-                    "You are unable to pick up the {}.",
-                    names.get(pickup.item).unwrap().name
-                ));
-                logs.push(&"You are carrying too many items!");
-                attempts.clear();
-                return;
+            let item_name = names.get(pickup.item).unwrap();
+
+            //Stackable items are merged into a matching stack the collector already carries
+            let is_match = |owner: Entity, name: &Name| {
+                owner == pickup.collected_by && name.name == item_name.name
+            };
+            let existing_stack = stackables.get(pickup.item).and_then(|_| {
+                (&entities, &backpack, &stackables, &names)
+                    .join()
+                    .find(|(_, pack, _, name)| is_match(pack.owner, name))
+                    .map(|(stack, ..)| stack)
+            });
+
+            if let Some(stack) = existing_stack {
+                let quantity = stackables.get(pickup.item).unwrap().quantity;
+                stackables.get_mut(stack).unwrap().quantity += quantity;
+                entities
+                    .delete(pickup.item)
+                    .expect("Unable to merge picked up item into stack");
+            } else {
+                positions.remove(pickup.item);
+                backpack
+                    .insert(
+                        pickup.item,
+                        InBackpack {
+                            owner: pickup.collected_by,
+                        },
+                    )
+                    .expect("Unable to insert backpack entry");
             }
-            positions.remove(pickup.item);
-            backpack
-                .insert(
-                    pickup.item,
-                    InBackpack {
-                        owner: pickup.collected_by,
-                    },
-                )
-                .expect("Unable to insert backpack entry");
 
             if pickup.collected_by == *player_ent {
                 logs.push(&format!(
                     "You pick up the {}.",
-                    names.get(pickup.item).unwrap().name
+                    identities.name_of(item_name)
                 ));
+
+                if artifacts.get(pickup.item).is_some() {
+                    *state = Game(Gameplay::Victory);
+                }
             }
         }
         attempts.clear();
@@ -71,10 +105,13 @@ impl<'a> System<'a> for ItemDropSystem {
     type SystemData = (
         Entities<'a>,
         ReadExpect<'a, Entity>,
+        ReadExpect<'a, Identities>,
+        Read<'a, LazyUpdate>,
         ReadStorage<'a, Name>,
         WriteExpect<'a, GameLog>,
         WriteStorage<'a, InBackpack>,
         WriteStorage<'a, Position>,
+        WriteStorage<'a, Stackable>,
         WriteStorage<'a, WantsToDropItem>,
     );
 
@@ -82,23 +119,53 @@ impl<'a> System<'a> for ItemDropSystem {
         let (
             entities,
             player_ent,
+            identities,
+            lazy,
             names,
             mut logs,
             mut backpack,
             mut positions,
+            mut stackables,
             mut intents_to_drop,
         ) = data;
         for (dropper, intent_to_drop) in (&entities, &intents_to_drop).join() {
             let dropper_pos = positions.get(dropper).unwrap().clone();
-            positions
-                .insert(intent_to_drop.item, dropper_pos)
-                .expect("Unable to add position to dropped item");
-            backpack.remove(intent_to_drop.item);
+            let item_name = names.get(intent_to_drop.item).unwrap();
+
+            match stackables.get_mut(intent_to_drop.item) {
+                //Part of a stack is split off into a copy of the item, keeping its charges, curse
+                //and affixes
+                Some(stack) if intent_to_drop.quantity < stack.quantity => {
+                    stack.quantity -= intent_to_drop.quantity;
+                    let (item, quantity) = (intent_to_drop.item, intent_to_drop.quantity);
+                    lazy.exec_mut(move |world| {
+                        let dropped = specs_helpers::clone_entity(world, item);
+                        world.write_storage::<InBackpack>().remove(dropped);
+                        world
+                            .write_storage::<Position>()
+                            .insert(dropped, dropper_pos)
+                            .expect("Unable to add position to dropped item");
+                        world
+                            .write_storage::<Stackable>()
+                            .insert(dropped, Stackable { quantity })
+                            .expect("Unable to set the size of the dropped stack");
+                    });
+                }
+                _ => {
+                    positions
+                        .insert(intent_to_drop.item, dropper_pos)
+                        .expect("Unable to add position to dropped item");
+                    backpack.remove(intent_to_drop.item);
+                }
+            }
+
             if dropper == *player_ent {
-                logs.push(&format!(
-                    "You drop the {}",
-                    names.get(intent_to_drop.item).unwrap().name
-                ));
+                let name = identities.name_of(item_name);
+                if intent_to_drop.quantity > 1 {
+                    logs.push(&format!("You drop {}x {}", intent_to_drop.quantity, name));
+                } else {
+                    logs.push(&format!("You drop the {name}"));
+                }
             }
         }
         intents_to_drop.clear();
@@ -112,6 +179,8 @@ impl<'a> System<'a> for ItemRemoveSystem {
     type SystemData = (
         Entities<'a>,
         ReadExpect<'a, Entity>,
+        ReadExpect<'a, Identities>,
+        ReadStorage<'a, Cursed>,
         ReadStorage<'a, Name>,
         WriteExpect<'a, GameLog>,
         WriteStorage<'a, WantsToRemoveItem>,
@@ -120,9 +189,28 @@ impl<'a> System<'a> for ItemRemoveSystem {
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, player_ent, names, mut logs, mut intents, mut equipped_items, mut backpacks) =
-            data;
+        let (
+            entities,
+            player_ent,
+            identities,
+            cursed,
+            names,
+            mut logs,
+            mut intents,
+            mut equipped_items,
+            mut backpacks,
+        ) = data;
         for (entity, intent) in (&entities, &intents).join() {
+            if cursed.get(intent.item).is_some() {
+                if entity == *player_ent {
+                    logs.push(&format!(
+                        "You can't remove the {}, it is cursed!",
+                        identities.name_of(names.get(intent.item).unwrap())
+                    ));
+                }
+                continue;
+            }
+
             equipped_items.remove(intent.item);
             backpacks
                 .insert(intent.item, InBackpack { owner: entity })
@@ -130,7 +218,7 @@ impl<'a> System<'a> for ItemRemoveSystem {
             if entity == *player_ent {
                 logs.push(&format!(
                     "You unequip the {}",
-                    names.get(intent.item).unwrap().name
+                    identities.name_of(names.get(intent.item).unwrap())
                 ))
             }
         }
@@ -139,159 +227,215 @@ impl<'a> System<'a> for ItemRemoveSystem {
     }
 }
 
-pub struct ItemUseSystem {}
+///Uses every item that an entity wants to use. Unlike the other item systems this runs on the
+///world directly, as the effects an item can have each need their own storages.
+pub fn use_items(ecs: &World) {
+    let intents = {
+        let entities = ecs.entities();
+        let intents = ecs.read_storage::<WantsToUseItem>();
+        (&entities, &intents)
+            .join()
+            .map(|(user, intent)| (user, intent.item, intent.target))
+            .collect::<Vec<_>>()
+    };
+    ecs.write_storage::<WantsToUseItem>().clear();
 
-impl<'a> System<'a> for ItemUseSystem {
-    #[allow(clippy::type_complexity)]
-    type SystemData = (
-        Entities<'a>,
-        ReadExpect<'a, Entity>,
-        ReadExpect<'a, Map>,
-        ReadStorage<'a, AreaOfEffect>,
-        ReadStorage<'a, Consumable>,
-        ReadStorage<'a, InflictsDamage>,
-        ReadStorage<'a, Name>,
-        ReadStorage<'a, ProvidesHealing>,
-        ReadStorage<'a, Equipment>,
-        WriteExpect<'a, GameLog>,
-        WriteStorage<'a, Equipped>,
-        WriteStorage<'a, InBackpack>,
-        WriteStorage<'a, CombatStats>,
-        WriteStorage<'a, SufferDamage>,
-        WriteStorage<'a, WantsToUseItem>,
-    );
+    for (user, item, target) in intents {
+        //Only a guard, as the inventory already turns down empty items the player picks
+        if is_out_of_charges(ecs, user, item) {
+            continue;
+        }
 
-    #[allow(clippy::too_many_lines)]
-    fn run(&mut self, data: Self::SystemData) {
-        let (
-            entities,
-            player_ent,
-            map,
-            aoe,
-            consumables,
-            damaging_items,
-            names,
-            healing_items,
-            equipment,
-            mut logs,
-            mut equipped_items,
-            mut backpack,
-            mut all_stats,
-            mut suffering,
-            mut intents,
-        ) = data;
+        let usage = EffectUse {
+            user,
+            item,
+            targets: find_targets(ecs, user, item, target),
+        };
 
-        for (user, intent) in (&entities, &intents).join() {
-            let mut used_item = true;
-
-            //Get all targets!
-            let mut targets: Vec<Entity> = Vec::new();
-            match intent.target {
-                None => targets.push(user),
-                Some(target) => match aoe.get(intent.item) {
-                    None => {
-                        let idx = map.xy_idx(target.x, target.y);
-                        for mob in &map.tile_content[idx] {
-                            targets.push(*mob);
-                        }
-                    }
-                    Some(area) => {
-                        let mut affected_tiles = rltk::field_of_view(target, area.radius, &*map);
-                        affected_tiles.retain(|t| (*map).in_bounds(Point::new(t.x, t.y)));
-                        for tile in &affected_tiles {
-                            let idx = map.xy_idx(tile.x, tile.y);
-                            for mob in &map.tile_content[idx] {
-                                targets.push(*mob);
-                            }
-                        }
-                    }
-                },
-            }
+        equip_item(ecs, &usage);
+        EFFECTS.apply(ecs, &usage);
+        identify_used_item(ecs, &usage);
 
-            //if the item heals...
-            if let Some(heal) = healing_items.get(intent.item) {
-                for target in &targets {
-                    if let Some(stats) = all_stats.get_mut(*target) {
-                        stats.hp = i32::min(stats.max_hp, stats.hp + heal.heal_amount);
-                        if user == *player_ent {
-                            logs.push(&format!(
-                                "You use the {}, healing {} hp.",
-                                names.get(intent.item).unwrap().name,
-                                heal.heal_amount
-                            ));
-                        }
-                        used_item = true;
-                    }
-                }
+        //Consumable, only the last item of a stack is deleted
+        if ecs.read_storage::<Consumable>().get(item).is_some() {
+            match ecs.write_storage::<Stackable>().get_mut(item) {
+                Some(stack) if stack.quantity > 1 => stack.quantity -= 1,
+                _ => ecs
+                    .entities()
+                    .delete(item)
+                    .expect("Deletion of consumable failed"),
             }
+        }
 
-            //if the item deals damage on use...
-            if let Some(damage) = damaging_items.get(intent.item) {
-                for mob in &targets {
-                    SufferDamage::new_damage(&mut suffering, *mob, damage.damage);
-                    if user == *player_ent && all_stats.get(*mob).is_some() {
-                        let mob_name = &names.get(*mob).unwrap().name;
-                        let item_name = &names.get(intent.item).unwrap().name;
-                        logs.push(&format!(
-                            "You use {} on {} inflicting {} damage.",
-                            item_name, mob_name, damage.damage
-                        ));
-                    }
-                    used_item = true;
+        use_charge(ecs, user, item);
+    }
+}
+
+///Empty items that didn't crumble can't be used anymore
+pub fn is_out_of_charges(ecs: &World, user: Entity, item: Entity) -> bool {
+    let out_of_charges = ecs
+        .read_storage::<Charges>()
+        .get(item)
+        .is_some_and(|charges| charges.remaining <= 0);
+    if out_of_charges && user == *ecs.fetch::<Entity>() {
+        let identities = ecs.fetch::<Identities>();
+        let names = ecs.read_storage::<Name>();
+        ecs.fetch_mut::<GameLog>().push(&format!(
+            "The {} has no charges left.",
+            identities.name_of(names.get(item).unwrap())
+        ));
+    }
+    out_of_charges
+}
+
+fn use_charge(ecs: &World, user: Entity, item: Entity) {
+    let crumbled = match ecs.write_storage::<Charges>().get_mut(item) {
+        Some(charges) => {
+            charges.remaining -= 1;
+            charges.remaining <= 0 && charges.crumbles
+        }
+        None => return,
+    };
+    if !crumbled {
+        return;
+    }
+
+    if user == *ecs.fetch::<Entity>() {
+        let identities = ecs.fetch::<Identities>();
+        let names = ecs.read_storage::<Name>();
+        ecs.fetch_mut::<GameLog>().push(&format!(
+            "The {} crumbles to dust.",
+            identities.name_of(names.get(item).unwrap())
+        ));
+    }
+    ecs.entities()
+        .delete(item)
+        .expect("Deletion of crumbled item failed");
+}
+
+///Items the player uses become known to them
+fn identify_used_item(ecs: &World, usage: &EffectUse) {
+    if usage.user != *ecs.fetch::<Entity>() {
+        return;
+    }
+
+    let names = ecs.read_storage::<Name>();
+    let mut identities = ecs.fetch_mut::<Identities>();
+    if let Some(name) = names.get(usage.item) {
+        let unknown_name = identities.name_of(name).to_string();
+        if identities.identify(name) {
+            ecs.fetch_mut::<GameLog>()
+                .push(&format!("The {} was a {}.", unknown_name, name.name));
+        }
+    }
+}
+
+fn find_targets(ecs: &World, user: Entity, item: Entity, target: Option<Point>) -> Vec<Entity> {
+    let map = ecs.fetch::<Map>();
+    let aoe = ecs.read_storage::<AreaOfEffect>();
+
+    let mut targets: Vec<Entity> = Vec::new();
+    match target {
+        None => targets.push(user),
+        Some(target) => match aoe.get(item) {
+            None => {
+                let idx = map.xy_idx(target.x, target.y);
+                for mob in &map.tile_content[idx] {
+                    targets.push(*mob);
                 }
             }
-
-            //If the item can be equipped...
-            if let Some(equipment) = equipment.get(intent.item) {
-                //De-equip all items that share a slot
-                let mut to_unequip = Vec::new();
-                for (item, already_equipped, name) in (&entities, &equipped_items, &names).join() {
-                    if already_equipped.owner == targets[0]
-                        && equipment.slot == already_equipped.slot
-                    {
-                        to_unequip.push(item);
-                        if targets[0] == *player_ent {
-                            logs.push(&format!("You unequip {}.", name.name));
-                        }
+            Some(area) => {
+                let mut affected_tiles = rltk::field_of_view(target, area.radius, &*map);
+                affected_tiles.retain(|t| (*map).in_bounds(Point::new(t.x, t.y)));
+                for tile in &affected_tiles {
+                    let idx = map.xy_idx(tile.x, tile.y);
+                    for mob in &map.tile_content[idx] {
+                        targets.push(*mob);
                     }
                 }
+            }
+        },
+    }
+    targets
+}
 
-                for item in &to_unequip {
-                    equipped_items.remove(*item);
-                    backpack
-                        .insert(*item, InBackpack { owner: targets[0] })
-                        .expect("Unable to put unequipped item into backpack");
-                }
+fn equip_item(ecs: &World, usage: &EffectUse) {
+    let equipment = ecs.read_storage::<Equipment>();
+    let Some(equipment) = equipment.get(usage.item) else {
+        return;
+    };
+    let Some(&wearer) = usage.targets.first() else {
+        return;
+    };
 
-                //Equip item
-                equipped_items
-                    .insert(
-                        intent.item,
-                        Equipped {
-                            owner: targets[0],
-                            slot: equipment.slot,
-                        },
-                    )
-                    .expect("Unable to equip desired item");
-                backpack.remove(intent.item);
+    let entities = ecs.entities();
+    let player_ent = ecs.fetch::<Entity>();
+    let names = ecs.read_storage::<Name>();
+    let identities = ecs.fetch::<Identities>();
+    let mut logs = ecs.fetch_mut::<GameLog>();
+    let mut equipped_items = ecs.write_storage::<Equipped>();
+    let mut backpack = ecs.write_storage::<InBackpack>();
+    let mut cursed = ecs.write_storage::<Cursed>();
 
-                //Inform if player is equipping
-                if targets[0] == *player_ent {
-                    logs.push(&format!(
-                        "You equip {}.",
-                        names.get(intent.item).unwrap().name
-                    ));
-                }
-            }
+    //Cursed items can't be swapped out of their slot
+    if let Some((_, name, _)) = (&equipped_items, &names, &cursed)
+        .join()
+        .find(|(equipped, _, _)| equipped.owner == wearer && equipped.slot == equipment.slot)
+    {
+        if wearer == *player_ent {
+            logs.push(&format!(
+                "You can't remove the {}, it is cursed!",
+                identities.name_of(name)
+            ));
+        }
+        return;
+    }
 
-            //Consumable
-            if consumables.get(intent.item).is_some() && used_item {
-                entities
-                    .delete(intent.item)
-                    .expect("Deletion of consumable failed");
+    //De-equip all items that share a slot
+    let mut to_unequip = Vec::new();
+    for (item, already_equipped, name) in (&entities, &equipped_items, &names).join() {
+        if already_equipped.owner == wearer && equipment.slot == already_equipped.slot {
+            to_unequip.push(item);
+            if wearer == *player_ent {
+                logs.push(&format!("You unequip {}.", identities.name_of(name)));
             }
         }
+    }
 
-        intents.clear();
+    for item in &to_unequip {
+        equipped_items.remove(*item);
+        backpack
+            .insert(*item, InBackpack { owner: wearer })
+            .expect("Unable to put unequipped item into backpack");
+    }
+
+    //Equip item
+    equipped_items
+        .insert(
+            usage.item,
+            Equipped {
+                owner: wearer,
+                slot: equipment.slot,
+            },
+        )
+        .expect("Unable to equip desired item");
+    backpack.remove(usage.item);
+
+    //Inform if player is equipping
+    if wearer == *player_ent {
+        logs.push(&format!(
+            "You equip {}.",
+            identities.name_of(names.get(usage.item).unwrap())
+        ));
+    }
+
+    if let Some(curse) = cursed.get_mut(usage.item) {
+        if wearer == *player_ent && !curse.revealed {
+            logs.push(&format!(
+                "The {} tightens around you. It is cursed!",
+                identities.name_of(names.get(usage.item).unwrap())
+            ));
+        }
+        curse.revealed = true;
     }
 }
@@ -0,0 +1,54 @@
+use crate::{
+    components::{Equipped, FieldOfView, LightSource, Position},
+    map_builder::map::Map,
+};
+use rltk::{field_of_view, DistanceAlg, Point};
+use specs::prelude::*;
+
+///Builds the light map of the level from the ambient light and every light source. Light sources
+///that are equipped shine from the position of their owner.
+pub struct LightingSystem {}
+
+impl<'a> System<'a> for LightingSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        ReadStorage<'a, Equipped>,
+        ReadStorage<'a, LightSource>,
+        ReadStorage<'a, Position>,
+        WriteExpect<'a, Map>,
+        WriteStorage<'a, FieldOfView>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (player_ent, equipped_items, light_sources, positions, mut map, mut fields_of_view) =
+            data;
+
+        let mut light = vec![map.ambient_light(); map.tiles.len()];
+        for (light_source, pos, equipped) in
+            (&light_sources, positions.maybe(), equipped_items.maybe()).join()
+        {
+            let Some(pos) = pos.or_else(|| equipped.and_then(|e| positions.get(e.owner))) else {
+                continue;
+            };
+
+            let center = Point::new(pos.x, pos.y);
+            for tile in field_of_view(center, light_source.range, &*map) {
+                if tile.x < 0 || tile.x >= map.width || tile.y < 0 || tile.y >= map.height {
+                    continue;
+                }
+                let distance = DistanceAlg::Pythagoras.distance2d(center, tile);
+                let intensity = 1.0 - distance / (light_source.range + 1) as f32;
+                let idx = map.xy_idx(tile.x, tile.y);
+                light[idx] = (light[idx] + intensity.max(0.0)).min(1.0);
+            }
+        }
+
+        //What the player can see depends on the light, so it has to be updated when it changes
+        if map.light != light {
+            map.light = light;
+            if let Some(fov) = fields_of_view.get_mut(*player_ent) {
+                fov.is_dirty = true;
+            }
+        }
+    }
+}
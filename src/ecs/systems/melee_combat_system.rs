@@ -1,11 +1,13 @@
 use super::ParticleBuilder;
 use crate::{
     constants::colors, CombatStats, DefenseBonus, Equipped, GameLog, MeleeDamageBonus, Name,
-    Position, SufferDamage, WantsToMelee,
+    Overburdened, Position, SufferDamage, WantsToMelee,
 };
 use rltk::{ColorPair, RGB};
 use specs::prelude::*;
 
+const OVERBURDENED_ATTACK_PENALTY: i32 = 2;
+
 pub struct MeleeCombatSystem {}
 
 impl<'a> System<'a> for MeleeCombatSystem {
@@ -17,6 +19,7 @@ impl<'a> System<'a> for MeleeCombatSystem {
         ReadStorage<'a, Equipped>,
         ReadStorage<'a, MeleeDamageBonus>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, Overburdened>,
         ReadStorage<'a, Position>,
         WriteExpect<'a, GameLog>,
         WriteExpect<'a, ParticleBuilder>,
@@ -32,6 +35,7 @@ impl<'a> System<'a> for MeleeCombatSystem {
             equipped_items,
             damage_bonuses,
             names,
+            overburdened,
             positions,
             mut game_log,
             mut particle_builder,
@@ -49,6 +53,9 @@ impl<'a> System<'a> for MeleeCombatSystem {
                         attack_bonus_sum += damage_bonus.bonus;
                     }
                 }
+                if overburdened.get(attacker).is_some() {
+                    attack_bonus_sum -= OVERBURDENED_ATTACK_PENALTY;
+                }
 
                 //If the target is alive
                 let target_stats = all_stats.get(attack.target).unwrap();
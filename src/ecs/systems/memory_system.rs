@@ -0,0 +1,54 @@
+use crate::{
+    components::{Hidden, Monster, Player, Position, Render},
+    map_builder::map::{Map, Memory, TileStatus},
+};
+use specs::prelude::*;
+
+///Remembers what the player sees on every visible tile, so it can still be drawn once out of view
+pub struct MemorySystem {}
+
+impl<'a> System<'a> for MemorySystem {
+    type SystemData = (
+        ReadStorage<'a, Hidden>,
+        ReadStorage<'a, Monster>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Render>,
+        WriteExpect<'a, Map>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (hidden, monsters, players, positions, renderables, mut map) = data;
+
+        //Whatever was remembered on a visible tile may have moved away since
+        for idx in 0..map.tiles.len() {
+            if map.is_tile_status_set(idx, TileStatus::Visible) {
+                map.memories[idx] = None;
+            }
+        }
+
+        //Only the entity drawn on top of a tile is remembered
+        let mut top_orders = vec![i32::MAX; map.tiles.len()];
+        for (pos, render, monster, (), ()) in (
+            &positions,
+            &renderables,
+            monsters.maybe(),
+            !&hidden,
+            !&players,
+        )
+            .join()
+        {
+            let idx = map.xy_idx(pos.x, pos.y);
+            if map.is_tile_status_set(idx, TileStatus::Visible)
+                && render.render_order < top_orders[idx]
+            {
+                top_orders[idx] = render.render_order;
+                map.memories[idx] = Some(Memory {
+                    glyph: render.glyph,
+                    color: render.colors.fg.to_rgb(),
+                    is_monster: monster.is_some(),
+                });
+            }
+        }
+    }
+}
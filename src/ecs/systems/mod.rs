@@ -1,15 +1,29 @@
+mod corpse_system;
 mod damage_system;
+mod encumbrance_system;
+mod hunger_system;
 mod item_systems;
+mod lighting_system;
 mod map_indexing_system;
 mod melee_combat_system;
+mod memory_system;
 mod monster_ai_system;
 mod particle_system;
+mod regeneration_system;
+mod trap_system;
 mod visibility_system;
 
+pub use corpse_system::*;
 pub use damage_system::*;
+pub use encumbrance_system::*;
+pub use hunger_system::*;
 pub use item_systems::*;
+pub use lighting_system::*;
 pub use map_indexing_system::*;
 pub use melee_combat_system::*;
+pub use memory_system::*;
 pub use monster_ai_system::*;
 pub use particle_system::*;
+pub use regeneration_system::*;
+pub use trap_system::*;
 pub use visibility_system::*;
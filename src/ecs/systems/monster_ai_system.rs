@@ -1,73 +1,258 @@
 use crate::{
-    components::{FieldOfView, Monster, Position, WantsToMelee},
-    map_builder::map::Map,
+    components::{
+        AreaOfEffect, BlocksTile, Charges, CombatStats, EntityMoved, Faction, FieldOfView,
+        Immobilized, InBackpack, InflictsDamage, Monster, Position, ProvidesHealing, Range,
+        WantsToMelee, WantsToUseItem,
+    },
+    map_builder::map::{Map, TileStatus},
+    raws::faction::{Reaction, DEFAULT_FACTION, FACTION_RAWS},
     state::{Gameplay, State, State::Game},
 };
-use rltk::Point;
+use rltk::{BaseMap, DistanceAlg, Point, RandomNumberGenerator};
 use specs::prelude::*;
+use std::collections::HashMap;
 
 pub struct MonsterAI {}
 impl<'a> System<'a> for MonsterAI {
     #[allow(clippy::type_complexity)]
     type SystemData = (
         Entities<'a>,
-        ReadExpect<'a, Point>,
-        ReadExpect<'a, Entity>,
         ReadExpect<'a, State>,
+        ReadExpect<'a, Entity>,
+        ReadStorage<'a, AreaOfEffect>,
+        ReadStorage<'a, BlocksTile>,
+        ReadStorage<'a, Charges>,
+        ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, Faction>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, InflictsDamage>,
         ReadStorage<'a, Monster>,
+        ReadStorage<'a, ProvidesHealing>,
+        ReadStorage<'a, Range>,
         WriteExpect<'a, Map>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, FieldOfView>,
         WriteStorage<'a, WantsToMelee>,
+        WriteStorage<'a, WantsToUseItem>,
+        WriteStorage<'a, Immobilized>,
+        WriteStorage<'a, EntityMoved>,
     );
 
+    #[allow(clippy::too_many_lines)]
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
-            player_pos,
-            player_ent,
             state,
+            player,
+            areas,
+            blockers,
+            charges,
+            all_stats,
+            factions,
+            backpack,
+            damaging_items,
             monsters,
-            map,
+            healing_items,
+            ranges,
+            mut map,
             mut positions,
             mut fields_of_view,
             mut attacks,
+            mut intents,
+            mut immobilized,
+            mut moved,
         ) = data;
 
         if *state != Game(Gameplay::MonsterTurn) {
             return;
         }
 
-        for (mut fov, mut pos, ent, _) in
-            (&mut fields_of_view, &mut positions, &entities, &monsters).join()
+        let faction_raws = FACTION_RAWS.lock().unwrap();
+        let mut rng = RandomNumberGenerator::new();
+
+        //Positions are collected up front, as the positions of monsters are changed below. Each move
+        //is written back to this snapshot so later monsters see where the others ended up.
+        //Anything saved before factions existed has none, and falls back to the default faction
+        let mut actors = (&entities, &positions, factions.maybe(), &all_stats)
+            .join()
+            .map(|(ent, pos, faction, _)| {
+                let faction = faction.map_or_else(
+                    || {
+                        if ent == *player {
+                            "Player"
+                        } else {
+                            DEFAULT_FACTION
+                        }
+                    },
+                    |faction| faction.name.as_str(),
+                );
+                (ent, Point::new(pos.x, pos.y), faction.to_string())
+            })
+            .collect::<Vec<_>>();
+
+        let mut carried_items: HashMap<Entity, Vec<Entity>> = HashMap::new();
+        for (item, pack) in (&entities, &backpack).join() {
+            carried_items.entry(pack.owner).or_default().push(item);
+        }
+
+        for (fov, pos, ent, faction, _) in (
+            &mut fields_of_view,
+            &mut positions,
+            &entities,
+            factions.maybe(),
+            &monsters,
+        )
+            .join()
         {
-            //If monster can see player attack if within range or approach
-            if fov.visible_tiles.contains(&*player_pos) {
-                let distance =
-                    rltk::DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), *player_pos);
-                if distance < 2.0 {
-                    attacks
+            let my_faction = faction.map_or(DEFAULT_FACTION, |faction| faction.name.as_str());
+            let my_pos = Point::new(pos.x, pos.y);
+            let mut nearest_target: Option<(Entity, Point, f32)> = None;
+            let mut threats = Vec::new();
+            let mut allies = vec![my_pos];
+
+            for (other, other_pos, other_faction) in &actors {
+                if *other == ent || !fov.visible_tiles.contains(other_pos) {
+                    continue;
+                }
+
+                let distance = DistanceAlg::Pythagoras.distance2d(my_pos, *other_pos);
+                match faction_raws.reaction(my_faction, other_faction) {
+                    Reaction::Attack => {
+                        if nearest_target.is_none_or(|(_, _, nearest)| distance < nearest) {
+                            nearest_target = Some((*other, *other_pos, distance));
+                        }
+                    }
+                    Reaction::Flee => threats.push(*other_pos),
+                    Reaction::Ignore => allies.push(*other_pos),
+                }
+            }
+
+            //Drink a healing item when badly hurt
+            let carried = carried_items.get(&ent).map_or(&[][..], Vec::as_slice);
+            let is_hurt = all_stats
+                .get(ent)
+                .is_some_and(|stats| stats.hp <= stats.max_hp / 2);
+            let healing_item = carried
+                .iter()
+                .find(|item| healing_items.get(**item).is_some());
+            if let (true, Some(item)) = (is_hurt, healing_item) {
+                intents
+                    .insert(
+                        ent,
+                        WantsToUseItem {
+                            item: *item,
+                            target: None,
+                        },
+                    )
+                    .expect("Unable to insert intent");
+                continue;
+            }
+
+            //Use a ranged item on a target that is out of reach
+            if let Some((_, target_pos, distance)) = nearest_target {
+                //Items without charges are refused when used, and a blast must not catch any allies
+                let ranged_item = carried.iter().find(|item| {
+                    let is_spent = charges
+                        .get(**item)
+                        .is_some_and(|charges| charges.remaining <= 0);
+                    let hits_ally = areas.get(**item).is_some_and(|area| {
+                        rltk::field_of_view(target_pos, area.radius, &*map)
+                            .iter()
+                            .any(|tile| allies.contains(tile))
+                    });
+                    damaging_items.get(**item).is_some()
+                        && !is_spent
+                        && !hits_ally
+                        && ranges
+                            .get(**item)
+                            .is_some_and(|range| distance < range.range as f32)
+                });
+                if let (true, Some(item)) = (distance >= 2.0, ranged_item) {
+                    intents
                         .insert(
                             ent,
-                            WantsToMelee {
-                                target: *player_ent,
+                            WantsToUseItem {
+                                item: *item,
+                                target: Some(target_pos),
                             },
                         )
+                        .expect("Unable to insert intent");
+                    continue;
+                }
+            }
+
+            let my_idx = map.xy_idx(pos.x, pos.y);
+            let next_idx = if !threats.is_empty() {
+                //Step to whichever neighbouring tile is furthest from the closest threat
+                let distance_to_threats = |idx: usize| {
+                    let tile = Point::new(idx as i32 % map.width, idx as i32 / map.width);
+                    threats
+                        .iter()
+                        .map(|threat| DistanceAlg::Pythagoras.distance2d(tile, *threat))
+                        .fold(f32::MAX, f32::min)
+                };
+                map.get_available_exits(my_idx)
+                    .iter()
+                    .map(|(idx, _)| (*idx, distance_to_threats(*idx)))
+                    .filter(|(_, distance)| *distance > distance_to_threats(my_idx))
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .map(|(idx, _)| idx)
+            } else if let Some((target, target_pos, distance)) = nearest_target {
+                //Attack if within range or approach
+                if distance < 2.0 {
+                    attacks
+                        .insert(ent, WantsToMelee { target })
                         .expect("Unable to insert attack");
+                    None
                 } else {
                     let path = rltk::a_star_search(
-                        map.xy_idx(pos.x, pos.y) as i32,
-                        map.xy_idx(player_pos.x, player_pos.y) as i32,
+                        my_idx as i32,
+                        map.xy_idx(target_pos.x, target_pos.y) as i32,
                         &*map,
                     );
 
                     if path.success && path.steps.len() > 1 {
-                        //Do note, that this does NOT check if the player is there
-                        pos.x = path.steps[1] as i32 % map.width;
-                        pos.y = path.steps[1] as i32 / map.width;
-                        fov.is_dirty = true;
+                        Some(path.steps[1])
+                    } else {
+                        None
                     }
                 }
+            } else {
+                //Nothing of interest is in sight, so wander about
+                let exits = map.get_available_exits(my_idx);
+                if exits.is_empty() {
+                    None
+                } else {
+                    let choice = (rng.roll_dice(1, exits.len() as i32) - 1) as usize;
+                    Some(exits[choice].0)
+                }
+            };
+
+            if let Some(next_idx) = next_idx {
+                //Monsters held in place struggle instead of moving
+                if let Some(held) = immobilized.get_mut(ent) {
+                    held.turns -= 1;
+                    if held.turns <= 0 {
+                        immobilized.remove(ent);
+                    }
+                    continue;
+                }
+
+                pos.x = next_idx as i32 % map.width;
+                pos.y = next_idx as i32 / map.width;
+                if let Some(actor) = actors.iter_mut().find(|(other, ..)| *other == ent) {
+                    actor.1 = Point::new(pos.x, pos.y);
+                }
+                fov.is_dirty = true;
+                moved
+                    .insert(ent, EntityMoved {})
+                    .expect("Unable to mark monster as moved");
+
+                //Keeps other monsters from moving onto the same tile this turn
+                if blockers.get(ent).is_some() {
+                    map.remove_tile_status(my_idx, TileStatus::Blocked);
+                    map.set_tile_status(next_idx, TileStatus::Blocked);
+                }
             }
         }
     }
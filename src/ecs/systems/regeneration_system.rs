@@ -0,0 +1,29 @@
+use crate::{
+    components::{CombatStats, Regeneration},
+    state::{Gameplay, State, State::Game},
+};
+use specs::prelude::*;
+
+pub struct RegenerationSystem {}
+
+impl<'a> System<'a> for RegenerationSystem {
+    type SystemData = (
+        ReadExpect<'a, State>,
+        ReadStorage<'a, Regeneration>,
+        WriteStorage<'a, CombatStats>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (state, regenerators, mut all_stats) = data;
+
+        if *state != Game(Gameplay::MonsterTurn) {
+            return;
+        }
+
+        for (stats, regen) in (&mut all_stats, &regenerators).join() {
+            if stats.hp > 0 {
+                stats.hp = i32::min(stats.max_hp, stats.hp + regen.amount);
+            }
+        }
+    }
+}
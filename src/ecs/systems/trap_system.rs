@@ -0,0 +1,97 @@
+use crate::{
+    components::{
+        EntityMoved, Hidden, Immobilized, Immobilizes, InflictsDamage, Name, Position,
+        SufferDamage, Teleports, Trap,
+    },
+    ecs::item_effects::{EffectUse, ItemEffect},
+    game_log::GameLog,
+    map_builder::map::{Map, TileStatus},
+};
+use rltk::Point;
+use specs::prelude::*;
+
+///Springs every trap an entity moved onto this turn. Like `use_items` this runs on the world
+///directly, as traps share their effects with items.
+pub fn trigger_traps(ecs: &World) {
+    let victims = {
+        let entities = ecs.entities();
+        let moved = ecs.read_storage::<EntityMoved>();
+        let positions = ecs.read_storage::<Position>();
+        (&entities, &moved, &positions)
+            .join()
+            .map(|(victim, _, pos)| (victim, Point::new(pos.x, pos.y)))
+            .collect::<Vec<_>>()
+    };
+    ecs.write_storage::<EntityMoved>().clear();
+
+    for (victim, pos) in victims {
+        let traps = {
+            let map = ecs.fetch::<Map>();
+            let traps = ecs.read_storage::<Trap>();
+            map.tile_content[map.xy_idx(pos.x, pos.y)]
+                .iter()
+                .filter(|ent| traps.get(**ent).is_some())
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        for trap in traps {
+            spring_trap(ecs, trap, victim, pos);
+        }
+    }
+}
+
+fn spring_trap(ecs: &World, trap: Entity, victim: Entity, pos: Point) {
+    //A sprung trap is no secret anymore
+    ecs.write_storage::<Hidden>().remove(trap);
+
+    {
+        let player_ent = ecs.fetch::<Entity>();
+        let map = ecs.fetch::<Map>();
+        let names = ecs.read_storage::<Name>();
+        let mut logs = ecs.fetch_mut::<GameLog>();
+        let trap_name = &names.get(trap).unwrap().name;
+        if victim == *player_ent {
+            logs.push(&format!("You trigger the {trap_name}!"));
+        } else if let (Some(name), true) = (
+            names.get(victim),
+            map.is_tile_status_set(map.xy_idx(pos.x, pos.y), TileStatus::Visible),
+        ) {
+            logs.push(&format!("{} triggers the {}!", name.name, trap_name));
+        }
+    }
+
+    if let Some(damage) = ecs.read_storage::<InflictsDamage>().get(trap) {
+        SufferDamage::new_damage(&mut ecs.write_storage(), victim, damage.damage);
+    }
+    if let Some(immobilizes) = ecs.read_storage::<Immobilizes>().get(trap) {
+        ecs.write_storage::<Immobilized>()
+            .insert(
+                victim,
+                Immobilized {
+                    turns: immobilizes.turns,
+                },
+            )
+            .expect("Unable to immobilize victim");
+    }
+    let teleports = ecs.read_storage::<Teleports>().get(trap).cloned();
+    if let Some(teleports) = teleports {
+        teleports.apply(
+            ecs,
+            &EffectUse {
+                user: victim,
+                item: trap,
+                targets: vec![victim],
+            },
+        );
+    }
+
+    if ecs
+        .read_storage::<Trap>()
+        .get(trap)
+        .is_some_and(|trap| trap.single_use)
+    {
+        ecs.entities()
+            .delete(trap)
+            .expect("Unable to delete single use trap");
+    }
+}
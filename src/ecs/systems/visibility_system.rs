@@ -1,23 +1,68 @@
+use super::ParticleBuilder;
 use crate::{
-    components::{FieldOfView, Position},
-    map_builder::map::{Map, TileStatus},
+    components::{FieldOfView, Hidden, Name, Position, Trap},
+    constants::colors,
+    game_log::GameLog,
+    map_builder::map::{Map, TileStatus, TileType},
 };
-use rltk::{field_of_view, Point};
+use rltk::{field_of_view, ColorPair, Point, RandomNumberGenerator, RGB};
 use specs::prelude::*;
 
+///The chance in percent that the player notices a secret door each time it comes into view
+const SECRET_DOOR_SPOT_CHANCE: i32 = 5;
+
+///Turns the secret door at `idx` into a door, and lets the player know it was found. Returns
+///whether there was a secret door to find.
+pub fn discover_secret_door(
+    map: &mut Map,
+    idx: usize,
+    logs: &mut GameLog,
+    particles: &mut ParticleBuilder,
+) -> bool {
+    let found = map.reveal_secret_door(idx);
+    if found {
+        logs.push(&"You find a secret door!");
+        particles.create_particle(
+            idx as i32 % map.width,
+            idx as i32 / map.width,
+            ColorPair::new(RGB::named(rltk::YELLOW), RGB::from(colors::BACKGROUND)),
+            rltk::to_cp437('+'),
+            400.0,
+        );
+    }
+    found
+}
+
 pub struct VisibilitySystem {}
 
 impl<'a> System<'a> for VisibilitySystem {
     type SystemData = (
         Entities<'a>,
         ReadExpect<'a, Entity>,
+        ReadStorage<'a, Name>,
         ReadStorage<'a, Position>,
+        ReadStorage<'a, Trap>,
+        WriteExpect<'a, GameLog>,
         WriteExpect<'a, Map>,
+        WriteExpect<'a, ParticleBuilder>,
         WriteStorage<'a, FieldOfView>,
+        WriteStorage<'a, Hidden>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, player_entity, positions, mut map, mut fields_of_view) = data;
+        let (
+            entities,
+            player_entity,
+            names,
+            positions,
+            traps,
+            mut logs,
+            mut map,
+            mut particles,
+            mut fields_of_view,
+            mut hidden,
+        ) = data;
+        let mut rng = RandomNumberGenerator::new();
 
         for (ent, fov, pos) in (&entities, &mut fields_of_view, &positions).join() {
             if fov.is_dirty {
@@ -27,14 +72,46 @@ impl<'a> System<'a> for VisibilitySystem {
                 fov.visible_tiles
                     .retain(|t| t.x >= 0 && t.x < map.width && t.y >= 0 && t.y < map.height);
                 if ent == *player_entity {
+                    //Monsters see in the dark, but the player can only make out lit tiles, or
+                    //the ones right next to them
+                    fov.visible_tiles.retain(|t| {
+                        map.is_lit(map.xy_idx(t.x, t.y))
+                            || ((t.x - pos.x).abs() <= 1 && (t.y - pos.y).abs() <= 1)
+                    });
                     for idx in 0..map.tile_status.len() {
                         map.remove_tile_status(idx, TileStatus::Visible);
                     }
+                    let mut found_door = false;
                     for vis in &fov.visible_tiles {
                         let idx = map.xy_idx(vis.x, vis.y);
                         map.set_tile_status(idx, TileStatus::Revealed);
                         map.set_tile_status(idx, TileStatus::Visible);
+
+                        //Secret doors and hidden traps may be spotted each time they come into view
+                        if map.tiles[idx] == TileType::SecretDoor
+                            && rng.roll_dice(1, 100) <= SECRET_DOOR_SPOT_CHANCE
+                        {
+                            found_door |=
+                                discover_secret_door(&mut map, idx, &mut logs, &mut particles);
+                        }
+                        for ent in &map.tile_content[idx] {
+                            let Some(trap) = traps.get(*ent) else {
+                                continue;
+                            };
+                            if hidden.get(*ent).is_some()
+                                && rng.roll_dice(1, 100) <= trap.spot_chance
+                            {
+                                hidden.remove(*ent);
+                                logs.push(&format!(
+                                    "You spot a {}.",
+                                    names.get(*ent).unwrap().name
+                                ));
+                            }
+                        }
                     }
+
+                    //Doors can be seen through, so the view has to be updated
+                    fov.is_dirty = found_door;
                 }
             }
         }
@@ -0,0 +1,168 @@
+use crate::{
+    camera,
+    constants::{colors, consoles},
+    ecs::{CombatStats, Hidden, Item, Position},
+    map_builder::map::{Map, TileStatus, TileType},
+    raws::config::{Config, KeyBinding},
+};
+use rltk::{Point, Rltk, RGB};
+use specs::{Join, World, WorldExt};
+use strum::AsRefStr;
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, AsRefStr)]
+pub enum MenuAction {
+    Look,
+    Attack,
+    #[strum(serialize = "Pick Up")]
+    PickUp,
+    #[strum(serialize = "Travel Here")]
+    TravelHere,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum ContextResult {
+    Cancel,
+    NoResponse,
+    Selected(MenuAction),
+}
+
+///Everything the player can do with the tile at `target`
+pub fn actions(world: &World, target: Point) -> Vec<MenuAction> {
+    let map = world.fetch::<Map>();
+    let player_pos = *world.fetch::<Point>();
+    let mut actions = vec![MenuAction::Look];
+    if target.x < 0 || target.x >= map.width || target.y < 0 || target.y >= map.height {
+        return actions;
+    }
+    let idx = map.xy_idx(target.x, target.y);
+
+    let is_adjacent = target != player_pos
+        && (target.x - player_pos.x).abs() <= 1
+        && (target.y - player_pos.y).abs() <= 1;
+    let all_stats = world.read_storage::<CombatStats>();
+    if is_adjacent
+        && map.is_tile_status_set(idx, TileStatus::Visible)
+        && map.tile_content[idx]
+            .iter()
+            .any(|ent| all_stats.get(*ent).is_some())
+    {
+        actions.push(MenuAction::Attack);
+    }
+
+    if target == player_pos && has_item(world, target) {
+        actions.push(MenuAction::PickUp);
+    }
+
+    let is_walkable = !matches!(map.tiles[idx], TileType::Wall | TileType::SecretDoor);
+    if target != player_pos && is_walkable && map.is_tile_status_set(idx, TileStatus::Revealed) {
+        actions.push(MenuAction::TravelHere);
+    }
+    actions
+}
+
+///What a left click on `target` does: attack, pick up, or else travel there
+pub fn default_action(world: &World, target: Point) -> Option<MenuAction> {
+    actions(world, target)
+        .into_iter()
+        .find(|action| *action != MenuAction::Look)
+}
+
+fn has_item(world: &World, target: Point) -> bool {
+    let items = world.read_storage::<Item>();
+    let positions = world.read_storage::<Position>();
+    let hidden = world.read_storage::<Hidden>();
+    (&items, &positions, !&hidden)
+        .join()
+        .any(|(_, pos, ())| pos.x == target.x && pos.y == target.y)
+}
+
+///Lists what the player can do with `target` next to it, chosen by letter or by clicking on it
+pub fn show(configs: &Config, world: &World, ctx: &mut Rltk, target: Point) -> ContextResult {
+    let (min_x, _, min_y, _) = camera::get_screen_bounds(world);
+    let actions = actions(world, target);
+    let screen = Point::new(target.x - min_x, target.y - min_y);
+
+    ctx.set_active_console(consoles::MAP_CONSOLE);
+    ctx.set_bg(screen.x, screen.y, RGB::named(rltk::MAGENTA));
+
+    let width = actions
+        .iter()
+        .map(|action| action.as_ref().len())
+        .max()
+        .unwrap_or(0) as i32
+        + 5;
+    let height = actions.len() as i32 + 1;
+    let Point { x, y } = camera::place_beside(world, screen, width, height);
+
+    ctx.set_active_console(consoles::HUD_CONSOLE);
+    ctx.draw_box(
+        x,
+        y,
+        width,
+        height,
+        RGB::from(colors::FOREGROUND),
+        RGB::from(colors::BACKGROUND),
+    );
+    for (offset, action) in actions.iter().enumerate() {
+        let line_y = y + 1 + offset as i32;
+        ctx.set(
+            x + 1,
+            line_y,
+            RGB::named(rltk::YELLOW),
+            RGB::from(colors::BACKGROUND),
+            97 + offset as rltk::FontCharType,
+        );
+        ctx.print(x + 2, line_y, format!(") {}", action.as_ref()));
+    }
+
+    if is_left_click(ctx) {
+        let (mouse_x, mouse_y) = ctx.mouse_pos();
+        let line = mouse_y - y - 1;
+        let on_menu = mouse_x > x && mouse_x < x + width && line >= 0;
+        return match actions.get(line as usize) {
+            Some(action) if on_menu => ContextResult::Selected(*action),
+            _ => ContextResult::Cancel,
+        };
+    }
+
+    let keys = &configs.keys;
+    let Some(key) = KeyBinding::pressed(ctx) else {
+        return ContextResult::NoResponse;
+    };
+    if keys.go_back.contains(&key) {
+        return ContextResult::Cancel;
+    }
+    let selection = rltk::letter_to_option(key.key);
+    if selection > -1 && selection < actions.len() as i32 {
+        ContextResult::Selected(actions[selection as usize])
+    } else {
+        ContextResult::NoResponse
+    }
+}
+
+///The right mouse button as of the last frame. Rltk only reports whether it is held down, which
+///would reopen the menu every frame until it is let go.
+#[derive(Default)]
+pub struct RightClick {
+    held: bool,
+    pressed: bool,
+}
+
+///Compares the right mouse button with the last frame, so this has to run every frame
+pub fn track_right_click(world: &World) {
+    let held = rltk::INPUT.lock().is_mouse_button_pressed(1);
+    let mut click = world.fetch_mut::<RightClick>();
+    click.pressed = held && !click.held;
+    click.held = held;
+}
+
+///Whether the right mouse button was just pressed
+pub fn is_right_click(world: &World) -> bool {
+    world.fetch::<RightClick>().pressed
+}
+
+///Whether the left mouse button was just pressed. The click flag of `ctx` is also raised when the
+///button is let go, which would otherwise act on the same tile twice.
+pub fn is_left_click(ctx: &Rltk) -> bool {
+    ctx.left_click && rltk::INPUT.lock().is_mouse_button_pressed(0)
+}
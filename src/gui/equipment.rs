@@ -0,0 +1,172 @@
+use crate::{
+    constants::{colors, consoles},
+    ecs::{
+        Cursed, DefenseBonus, Equipment, EquipmentSlot, Equipped, InBackpack, MeleeDamageBonus,
+        Name,
+    },
+    raws::config::{Config, KeyBinding},
+};
+use rltk::{Rltk, RGB};
+use specs::{Entity, Join, World, WorldExt};
+use strum::IntoEnumIterator;
+
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum EquipResult {
+    Cancel,
+    NoResponse,
+    SelectedSlot(EquipmentSlot),
+    Equip(Entity),
+    Remove(Entity),
+}
+
+//Base locations
+const BASE_X: i32 = 3;
+const BASE_Y: i32 = 4;
+const WIDTH: i32 = 60;
+
+///Shows every slot of the player alongside the item equipped in it
+pub fn show(configs: &Config, world: &World, ctx: &mut Rltk) -> EquipResult {
+    let player_ent = world.fetch::<Entity>();
+    let names = world.read_storage::<Name>();
+    let equipped_items = world.read_storage::<Equipped>();
+    let entities = world.entities();
+
+    let slots = EquipmentSlot::iter().collect::<Vec<_>>();
+    draw_frame(ctx, "Equipment", slots.len());
+
+    for (offset, slot) in slots.iter().enumerate() {
+        let item_name = (&entities, &equipped_items, &names)
+            .join()
+            .find(|(_, equipped, _)| equipped.owner == *player_ent && equipped.slot == *slot)
+            .map_or_else(
+                || "-".to_string(),
+                |(item, _, name)| format!("{}{}", name.name, describe_worn(world, item)),
+            );
+        let y = BASE_Y + offset as i32;
+        draw_letter(ctx, y, offset);
+        ctx.print(BASE_X + 4, y, format!("{:<13}{}", slot.as_ref(), item_name));
+    }
+
+    let keys = &configs.keys;
+    if let Some(key) = KeyBinding::pressed(ctx) {
+        if keys.go_back.contains(&key) {
+            return EquipResult::Cancel;
+        }
+        let selection = rltk::letter_to_option(key.key);
+        if selection > -1 && selection < slots.len() as i32 {
+            return EquipResult::SelectedSlot(slots[selection as usize]);
+        }
+    }
+    EquipResult::NoResponse
+}
+
+///Shows the item in `slot`, which can be removed, and every carried item that fits the slot
+pub fn show_slot(
+    configs: &Config,
+    world: &World,
+    ctx: &mut Rltk,
+    slot: EquipmentSlot,
+) -> EquipResult {
+    let player_ent = world.fetch::<Entity>();
+    let names = world.read_storage::<Name>();
+    let equipped_items = world.read_storage::<Equipped>();
+    let backpack_items = world.read_storage::<InBackpack>();
+    let equipment = world.read_storage::<Equipment>();
+    let entities = world.entities();
+
+    let mut options = Vec::new();
+    if let Some((item, _, name)) = (&entities, &equipped_items, &names)
+        .join()
+        .find(|(_, equipped, _)| equipped.owner == *player_ent && equipped.slot == slot)
+    {
+        options.push((EquipResult::Remove(item), format!("Remove {}", name.name)));
+    }
+    for (item, _, _, name) in (&entities, &backpack_items, &equipment, &names)
+        .join()
+        .filter(|(_, pack, equipment, _)| pack.owner == *player_ent && equipment.slot == slot)
+    {
+        options.push((EquipResult::Equip(item), format!("Equip {}", name.name)));
+    }
+
+    draw_frame(ctx, slot.as_ref(), options.len().max(1));
+
+    if options.is_empty() {
+        ctx.print(BASE_X + 1, BASE_Y, "Nothing fits this slot.");
+    }
+    for (offset, (_, text)) in options.iter().enumerate() {
+        let y = BASE_Y + offset as i32;
+        draw_letter(ctx, y, offset);
+        ctx.print(BASE_X + 4, y, text);
+    }
+
+    let keys = &configs.keys;
+    if let Some(key) = KeyBinding::pressed(ctx) {
+        if keys.go_back.contains(&key) {
+            return EquipResult::Cancel;
+        }
+        let selection = rltk::letter_to_option(key.key);
+        if selection > -1 && selection < options.len() as i32 {
+            return options[selection as usize].0;
+        }
+    }
+    EquipResult::NoResponse
+}
+
+///The bonuses of a worn item, and whether it is cursed. These are only known once it is worn.
+fn describe_worn(world: &World, item: Entity) -> String {
+    let mut bonuses = Vec::new();
+    if let Some(defense) = world.read_storage::<DefenseBonus>().get(item) {
+        bonuses.push(format!("{:+} def", defense.bonus));
+    }
+    if let Some(damage) = world.read_storage::<MeleeDamageBonus>().get(item) {
+        bonuses.push(format!("{:+} dmg", damage.bonus));
+    }
+
+    let bonuses = if bonuses.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", bonuses.join(", "))
+    };
+    let curse = world
+        .read_storage::<Cursed>()
+        .get(item)
+        .filter(|curse| curse.revealed)
+        .map_or("", |_| " (cursed)");
+    bonuses + curse
+}
+
+fn draw_frame(ctx: &mut Rltk, title: &str, rows: usize) {
+    ctx.set_active_console(consoles::HUD_CONSOLE);
+    ctx.draw_box(
+        BASE_X - 1,
+        BASE_Y - 2,
+        WIDTH,
+        rows as i32 + 3,
+        RGB::from(colors::FOREGROUND),
+        RGB::from(colors::BACKGROUND),
+    );
+    ctx.print_color(
+        BASE_X + 1,
+        BASE_Y - 2,
+        RGB::named(rltk::YELLOW),
+        RGB::from(colors::BACKGROUND),
+        title,
+    );
+}
+
+fn draw_letter(ctx: &mut Rltk, y: i32, offset: usize) {
+    ctx.set(
+        BASE_X + 1,
+        y,
+        RGB::named(rltk::YELLOW),
+        RGB::from(colors::BACKGROUND),
+        97 + offset as rltk::FontCharType,
+    );
+    ctx.set(
+        BASE_X + 2,
+        y,
+        RGB::from(colors::FOREGROUND),
+        RGB::from(colors::BACKGROUND),
+        rltk::to_cp437(')'),
+    );
+}
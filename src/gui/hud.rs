@@ -1,6 +1,6 @@
 use crate::{
     constants::{colors, consoles},
-    ecs::CombatStats,
+    ecs::{CombatStats, HungerClock, HungerState},
     rex_assets,
 };
 use rltk::{Rltk, RGB};
@@ -45,4 +45,23 @@ pub fn show(world: &World, ctx: &mut Rltk) {
             );
         }
     }
+
+    //Show hunger on the empty row below the stats, only when it isn't normal
+    if let Some(clock) = world.read_component::<HungerClock>().get(*player_entity) {
+        let color = match clock.state {
+            HungerState::WellFed => Some(RGB::named(rltk::GREEN)),
+            HungerState::Normal => None,
+            HungerState::Hungry => Some(RGB::named(rltk::ORANGE)),
+            HungerState::Starving => Some(RGB::named(rltk::RED)),
+        };
+        if let Some(color) = color {
+            ctx.print_color(
+                58,
+                7,
+                color,
+                RGB::named(colors::BACKGROUND),
+                clock.state.as_ref(),
+            );
+        }
+    }
 }
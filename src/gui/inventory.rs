@@ -1,20 +1,35 @@
 use crate::{
     constants::{colors, consoles},
-    ecs::{Equipped, InBackpack, Name},
-    raws::config::Config,
+    ecs::{
+        carried_weight, carry_capacity, identification::Identities, Charges, CombatStats, Equipped,
+        InBackpack, Name, Stackable, Weight,
+    },
+    raws::config::{Config, KeyBinding},
     rex_assets,
     state::{Gameplay, State, State::Game},
 };
 use rltk::{Rltk, RGB};
 use specs::{Entity, Join, World, WorldExt};
 
+//Items are picked by letter, so no more items are shown at once than there are letters
+const PAGE_SIZE: usize = 26;
+
 #[derive(PartialEq, Copy, Clone)]
 pub enum InvResult {
     Cancel,
     NoResponse,
+    Page(usize),
     Selected(Entity),
 }
 
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum AmountResult {
+    Cancel,
+    NoResponse,
+    Changed(i32),
+    Selected(i32),
+}
+
 #[derive(PartialEq, Copy, Clone, Debug)]
 pub enum InvMode {
     Use,
@@ -22,10 +37,14 @@ pub enum InvMode {
     Remove,
 }
 
-pub fn show(configs: &Config, world: &mut World, ctx: &mut Rltk) -> InvResult {
+///Lists the items of the player a page at a time, turning the pages with the left and right keys
+pub fn show(configs: &Config, world: &mut World, ctx: &mut Rltk, page: usize) -> InvResult {
     let player_ent = world.fetch::<Entity>();
     let current_state = world.fetch::<State>();
+    let identities = world.fetch::<Identities>();
     let names = world.read_storage::<Name>();
+    let stackables = world.read_storage::<Stackable>();
+    let charges = world.read_storage::<Charges>();
     let entities = world.entities();
 
     //Get all relevant items
@@ -33,7 +52,10 @@ pub fn show(configs: &Config, world: &mut World, ctx: &mut Rltk) -> InvResult {
     //if arms are of different types.
     #[allow(clippy::filter_map)]
     let relevant_entities = {
-        if *current_state == Game(Gameplay::Inventory(InvMode::Remove)) {
+        if matches!(
+            *current_state,
+            Game(Gameplay::Inventory(InvMode::Remove, _))
+        ) {
             let equipped_items = world.read_storage::<Equipped>();
             (&equipped_items, &names, &entities)
                 .join()
@@ -58,8 +80,24 @@ pub fn show(configs: &Config, world: &mut World, ctx: &mut Rltk) -> InvResult {
     let base_x = 3;
     let base_y = 4;
 
+    draw_weight(world, ctx, *player_ent);
+
+    let pages = relevant_entities.len().div_ceil(PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+    let shown =
+        &relevant_entities[page * PAGE_SIZE..relevant_entities.len().min((page + 1) * PAGE_SIZE)];
+    if pages > 1 {
+        ctx.print_color(
+            base_x + 1,
+            58,
+            RGB::named(rltk::YELLOW),
+            RGB::from(colors::BACKGROUND),
+            format!("< Page {}/{} >", page + 1, pages),
+        );
+    }
+
     //Print out relevant items
-    for (offset, (name, _)) in relevant_entities.iter().enumerate() {
+    for (offset, (name, item)) in shown.iter().enumerate() {
         let y = base_y + offset as i32;
         ctx.set(
             base_x + 1,
@@ -75,21 +113,125 @@ pub fn show(configs: &Config, world: &mut World, ctx: &mut Rltk) -> InvResult {
             RGB::from(colors::BACKGROUND),
             rltk::to_cp437(')'),
         );
-        ctx.print(base_x + 4, y, &name.name.to_string());
+        let name = identities.name_of(name);
+        let name = match stackables.get(*item) {
+            Some(stack) if stack.quantity > 1 => format!("{}x {}", stack.quantity, name),
+            _ => name.to_string(),
+        };
+        match charges.get(*item) {
+            Some(charges) if charges.remaining == 1 => {
+                ctx.print(base_x + 4, y, format!("{name} (1 charge)"));
+            }
+            Some(charges) => ctx.print(
+                base_x + 4,
+                y,
+                format!("{} ({} charges)", name, charges.remaining),
+            ),
+            None => ctx.print(base_x + 4, y, name),
+        }
     }
 
     //Respond to players response
     let keys = &configs.keys;
-    if let Some(key) = ctx.key {
-        return if key == keys.go_back {
-            InvResult::Cancel
+    let Some(key) = KeyBinding::pressed(ctx) else {
+        return InvResult::NoResponse;
+    };
+    //Letters always pick an item, even when they are also bound to turning the page
+    let selection = rltk::letter_to_option(key.key);
+    if keys.go_back.contains(&key) {
+        InvResult::Cancel
+    } else if selection > -1 {
+        shown
+            .get(selection as usize)
+            .map_or(InvResult::NoResponse, |(_, item)| {
+                InvResult::Selected(*item)
+            })
+    } else if keys.move_left.contains(&key) && page > 0 {
+        InvResult::Page(page - 1)
+    } else if keys.move_right.contains(&key) && page + 1 < pages {
+        InvResult::Page(page + 1)
+    } else {
+        InvResult::NoResponse
+    }
+}
+
+///Shown in place of the slots of the backpack
+fn draw_weight(world: &World, ctx: &mut Rltk, player_ent: Entity) {
+    let weight = carried_weight(
+        player_ent,
+        &world.read_storage::<Weight>(),
+        &world.read_storage::<Stackable>(),
+        &world.read_storage::<InBackpack>(),
+        &world.read_storage::<Equipped>(),
+    );
+    let capacity = world
+        .read_storage::<CombatStats>()
+        .get(player_ent)
+        .map_or(0, carry_capacity);
+    let weight_color = if weight > capacity {
+        RGB::named(rltk::RED)
+    } else {
+        RGB::from(colors::FOREGROUND)
+    };
+    ctx.print_color(
+        2,
+        2,
+        weight_color,
+        RGB::from(colors::BACKGROUND),
+        format!("{:<11}", format!("Wt {}/{}", weight, capacity)),
+    );
+}
+
+///Lets the player choose how many items of a stack to drop
+pub fn show_drop_amount(
+    configs: &Config,
+    world: &World,
+    ctx: &mut Rltk,
+    item: Entity,
+    amount: i32,
+) -> AmountResult {
+    let identities = world.fetch::<Identities>();
+    let names = world.read_storage::<Name>();
+    let max_amount = world
+        .read_storage::<Stackable>()
+        .get(item)
+        .map_or(1, |stack| stack.quantity);
+
+    ctx.set_active_console(consoles::HUD_CONSOLE);
+    let assets = world.fetch::<rex_assets::RexAssets>();
+    ctx.render_xp_sprite(&assets.inventory, 0, 0);
+
+    let base_x = 3;
+    let base_y = 4;
+    ctx.print(
+        base_x + 1,
+        base_y,
+        format!(
+            "Drop how many {}?",
+            identities.name_of(names.get(item).unwrap())
+        ),
+    );
+    ctx.print_color(
+        base_x + 1,
+        base_y + 2,
+        RGB::named(rltk::YELLOW),
+        RGB::from(colors::BACKGROUND),
+        format!("< {amount} / {max_amount} >"),
+    );
+
+    let keys = &configs.keys;
+    if let Some(key) = KeyBinding::pressed(ctx) {
+        return if keys.go_back.contains(&key) {
+            AmountResult::Cancel
+        } else if keys.select.contains(&key) {
+            AmountResult::Selected(amount)
+        } else if keys.move_up.contains(&key) || keys.move_right.contains(&key) {
+            AmountResult::Changed(i32::min(amount + 1, max_amount))
+        } else if keys.move_down.contains(&key) || keys.move_left.contains(&key) {
+            AmountResult::Changed(i32::max(amount - 1, 1))
         } else {
-            let selection = rltk::letter_to_option(key);
-            if selection > -1 && selection < relevant_entities.len() as i32 {
-                return InvResult::Selected(relevant_entities[selection as usize].1);
-            }
-            InvResult::NoResponse
+            AmountResult::NoResponse
         };
     }
-    InvResult::NoResponse
+    AmountResult::NoResponse
 }
@@ -0,0 +1,195 @@
+use crate::{
+    camera,
+    constants::{colors, consoles},
+    ecs::{
+        identification::Identities, Charges, CombatStats, Consumable, Equipment, EquipmentSlot,
+        Equipped, Hidden, Item, Name, Position, ProvidesFood, Stackable, Weight,
+    },
+    map_builder::map::{Map, TileStatus, TileType},
+    raws::config::{Config, KeyBinding},
+};
+use rltk::{Point, Rltk, RGB};
+use specs::{Entity, Join, World, WorldExt};
+
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum LookResult {
+    Cancel,
+    NoResponse,
+    Moved(Point),
+}
+
+///Moves a cursor around the map with the movement keys, describing whatever is under it
+pub fn show(configs: &Config, world: &World, ctx: &mut Rltk, cursor: Point) -> LookResult {
+    let (min_x, _, min_y, _) = camera::get_screen_bounds(world);
+
+    ctx.set_active_console(consoles::MAP_CONSOLE);
+    ctx.print_color(
+        5,
+        0,
+        RGB::named(rltk::YELLOW),
+        RGB::from(colors::BACKGROUND),
+        "Look Around: ",
+    );
+    let screen = Point::new(cursor.x - min_x, cursor.y - min_y);
+    ctx.set_bg(screen.x, screen.y, RGB::named(rltk::MAGENTA));
+    draw_description(world, ctx, cursor, screen);
+
+    let keys = &configs.keys;
+    let Some(key) = KeyBinding::pressed(ctx) else {
+        return LookResult::NoResponse;
+    };
+    if keys.go_back.contains(&key) {
+        return LookResult::Cancel;
+    }
+    let Some((dx, dy)) = keys.direction(key) else {
+        return LookResult::NoResponse;
+    };
+
+    //The cursor is kept to the part of the map that is on screen
+    let moved = Point::new(cursor.x + dx, cursor.y + dy);
+    if camera::is_on_screen(world, Point::new(moved.x - min_x, moved.y - min_y)) {
+        LookResult::Moved(moved)
+    } else {
+        LookResult::NoResponse
+    }
+}
+
+///Describes whatever is under the mouse while it hovers over the map
+pub fn show_tooltip(world: &World, ctx: &mut Rltk) {
+    let (min_x, _, min_y, _) = camera::get_screen_bounds(world);
+
+    ctx.set_active_console(consoles::MAP_CONSOLE);
+    let (mouse_x, mouse_y) = ctx.mouse_pos();
+    let screen = Point::new(mouse_x, mouse_y);
+    if camera::is_on_screen(world, screen) {
+        let target = Point::new(mouse_x + min_x, mouse_y + min_y);
+        draw_description(world, ctx, target, screen);
+    }
+}
+
+///Draws the description of `target` in a box next to where it is on screen
+fn draw_description(world: &World, ctx: &mut Rltk, target: Point, screen: Point) {
+    let lines = describe(world, target);
+    let width = lines.iter().map(String::len).max().unwrap_or(0) as i32 + 2;
+    let height = lines.len() as i32 + 1;
+    let Point { x, y } = camera::place_beside(world, screen, width, height);
+
+    ctx.set_active_console(consoles::HUD_CONSOLE);
+    ctx.draw_box(
+        x,
+        y,
+        width,
+        height,
+        RGB::from(colors::FOREGROUND),
+        RGB::from(colors::BACKGROUND),
+    );
+    for (offset, line) in lines.iter().enumerate() {
+        ctx.print(x + 1, y + 1 + offset as i32, line);
+    }
+}
+
+///The tile at `target` and, while it is in view, everything on it
+fn describe(world: &World, target: Point) -> Vec<String> {
+    let map = world.fetch::<Map>();
+    if target.x < 0 || target.x >= map.width || target.y < 0 || target.y >= map.height {
+        return vec!["Nothing".to_string()];
+    }
+    let idx = map.xy_idx(target.x, target.y);
+    if !map.is_tile_status_set(idx, TileStatus::Revealed) {
+        return vec!["Unexplored".to_string()];
+    }
+
+    let tile = match map.tiles[idx] {
+        TileType::Wall | TileType::SecretDoor => "Wall",
+        TileType::Door => "Door",
+        TileType::Floor => "Floor",
+        TileType::StairsDown => "Stairs leading down",
+    };
+    if !map.is_tile_status_set(idx, TileStatus::Visible) {
+        return vec![format!("{tile} (out of sight)")];
+    }
+
+    let player_ent = world.fetch::<Entity>();
+    let entities = world.entities();
+    let names = world.read_storage::<Name>();
+    let positions = world.read_storage::<Position>();
+    let hidden = world.read_storage::<Hidden>();
+    let items = world.read_storage::<Item>();
+    let all_stats = world.read_storage::<CombatStats>();
+    let identities = world.fetch::<Identities>();
+
+    let mut lines = vec![tile.to_string()];
+    for (ent, name, pos, ()) in (&entities, &names, &positions, !&hidden).join() {
+        if pos.x != target.x || pos.y != target.y {
+            continue;
+        }
+        if ent == *player_ent {
+            lines.push("You".to_string());
+            continue;
+        }
+
+        lines.push(identities.name_of(name).to_string());
+        if let Some(stats) = all_stats.get(ent) {
+            lines.push(format!(" {}", health_state(stats)));
+        }
+        if let Some(weapon) = wielded_weapon(world, ent) {
+            lines.push(format!(" Wielding a {weapon}"));
+        }
+        if items.get(ent).is_some() {
+            lines.push(format!(" {}", describe_item(world, ent)));
+        }
+    }
+    lines
+}
+
+fn health_state(stats: &CombatStats) -> &'static str {
+    match stats.hp * 4 / stats.max_hp.max(1) {
+        4.. => "Unhurt",
+        3 => "Lightly wounded",
+        2 => "Wounded",
+        1 => "Badly wounded",
+        _ => "Nearly dead",
+    }
+}
+
+fn wielded_weapon(world: &World, owner: Entity) -> Option<String> {
+    let names = world.read_storage::<Name>();
+    let equipped_items = world.read_storage::<Equipped>();
+    let identities = world.fetch::<Identities>();
+    (&equipped_items, &names)
+        .join()
+        .find(|(equipped, _)| {
+            equipped.owner == owner && equipped.slot == EquipmentSlot::PrimaryHand
+        })
+        .map(|(_, name)| identities.name_of(name).to_string())
+}
+
+///A short summary of what an item is, made from its components
+fn describe_item(world: &World, item: Entity) -> String {
+    let mut traits = Vec::new();
+    if let Some(equipment) = world.read_storage::<Equipment>().get(item) {
+        traits.push(format!("{} equipment", equipment.slot.as_ref()));
+    }
+    if world.read_storage::<ProvidesFood>().get(item).is_some() {
+        traits.push("Edible".to_string());
+    } else if world.read_storage::<Consumable>().get(item).is_some() {
+        traits.push("Single use".to_string());
+    }
+    if let Some(charges) = world.read_storage::<Charges>().get(item) {
+        traits.push(format!("{} charges left", charges.remaining));
+    }
+    if let Some(stack) = world.read_storage::<Stackable>().get(item) {
+        if stack.quantity > 1 {
+            traits.push(format!("{} in the stack", stack.quantity));
+        }
+    }
+    if let Some(weight) = world.read_storage::<Weight>().get(item) {
+        traits.push(format!("Weighs {}", weight.weight));
+    }
+
+    if traits.is_empty() {
+        "An item".to_string()
+    } else {
+        traits.join(", ")
+    }
+}
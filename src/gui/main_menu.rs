@@ -1,6 +1,6 @@
 use crate::{
     constants::{colors, consoles},
-    raws::config::Config,
+    raws::config::{Config, KeyBinding},
     rex_assets::RexAssets,
     state::MainOption,
 };
@@ -37,12 +37,12 @@ pub fn show(
 
     let keys = &configs.keys;
 
-    if let Some(key) = ctx.key {
-        if key == keys.select {
+    if let Some(key) = KeyBinding::pressed(ctx) {
+        if keys.select.contains(&key) {
             return (current_state, true);
-        } else if key == keys.move_up {
+        } else if keys.move_up.contains(&key) {
             return (current_state.up(), false);
-        } else if key == keys.move_down {
+        } else if keys.move_down.contains(&key) {
             return (current_state.down(), false);
         }
     }
@@ -1,9 +1,13 @@
+pub mod context_menu;
+pub mod equipment;
 pub mod game_over;
 pub mod hud;
 pub mod inventory;
+pub mod look;
 pub mod main_menu;
 pub mod settings;
 pub mod targeting;
+pub mod victory;
 
 //todo: Split the logic from each section from the art.
-// For the single responsiblity principle
\ No newline at end of file
+// For the single responsiblity principle
@@ -1,6 +1,6 @@
 use crate::{
     constants::{colors, consoles},
-    raws::config::Config,
+    raws::config::{Config, KeyBinding},
     rex_assets::RexAssets,
     state::AudioOption,
 };
@@ -44,14 +44,14 @@ pub fn show(
     }
 
     let keys = &configs.keys;
-    if let Some(key) = ctx.key {
-        if key == keys.go_back {
+    if let Some(key) = KeyBinding::pressed(ctx) {
+        if keys.go_back.contains(&key) {
             return AudioOption::Back;
-        } else if key == keys.move_up {
+        } else if keys.move_up.contains(&key) {
             return current_option.up();
-        } else if key == keys.move_down {
+        } else if keys.move_down.contains(&key) {
             return current_option.down();
-        } else if key == keys.move_left {
+        } else if keys.move_left.contains(&key) {
             match current_option {
                 AudioOption::MasterVolume => {
                     if audio.master_volume > 0 {
@@ -81,7 +81,7 @@ pub fn show(
                 }
                 AudioOption::Back => (),
             }
-        } else if key == keys.move_right {
+        } else if keys.move_right.contains(&key) {
             match current_option {
                 AudioOption::MasterVolume => {
                     if audio.master_volume < 25 {
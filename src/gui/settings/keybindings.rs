@@ -1,14 +1,36 @@
 use crate::{
     constants::{colors, consoles},
-    raws::config::Config,
+    raws::config::{Config, KeyBinding},
     rex_assets::RexAssets,
     state::KeyBindingOption,
 };
 use enum_cycling::IntoEnumCycle;
 use rltk::{Rltk, VirtualKeyCode, RGB};
-use std::sync::atomic::{AtomicBool, Ordering};
 use strum::IntoEnumIterator;
 
+///How many keys a single action can be bound to
+const MAX_BINDINGS: usize = 3;
+
+//Where the bound keys are printed, right of the labels
+const KEYS_X: i32 = 44;
+
+///Why the last key pressed while choosing a binding wasn't accepted
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Warning {
+    ///The key is bound to another action, and is moved over if it is pressed again
+    Conflict(KeyBinding, KeyBindingOption),
+    ///Taking the key away would leave the action without any key at all
+    OnlyKey(KeyBinding, KeyBindingOption),
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum KeySelectResult {
+    Cancel,
+    NoResponse,
+    Changed,
+    Warned(Warning),
+}
+
 pub fn show(
     configs: &mut Config,
     ctx: &mut Rltk,
@@ -22,30 +44,33 @@ pub fn show(
 
     let keys = &configs.keys;
 
-    if let Some(key) = ctx.key {
-        if key == keys.move_up {
+    if let Some(key) = KeyBinding::pressed(ctx) {
+        if keys.move_up.contains(&key) {
             return (current_option.up(), false);
-        } else if key == keys.move_down {
+        } else if keys.move_down.contains(&key) {
             return (current_option.down(), false);
-        } else if key == keys.go_back {
+        } else if keys.go_back.contains(&key) {
             return (KeyBindingOption::Back, false);
-        } else if key == keys.select {
+        } else if keys.select.contains(&key) {
+            if current_option == KeyBindingOption::ResetDefaults {
+                configs.keys = Config::default().keys;
+                return (current_option, false);
+            }
             return (current_option, true);
         }
     }
     (current_option, false)
 }
 
+///Binds the next key pressed to `current_option`, or unbinds it if it already was. `warning` is
+///why the key pressed before wasn't accepted, if it wasn't.
 pub fn key_selected(
     configs: &mut Config,
     ctx: &mut Rltk,
     current_option: KeyBindingOption,
+    warning: Option<Warning>,
     assets: &RexAssets,
-) -> bool {
-    lazy_static::lazy_static! {
-        static ref BAD_KEY_CHOSEN: AtomicBool = AtomicBool::new(false);
-    }
-
+) -> KeySelectResult {
     ctx.render_xp_sprite(&assets.keybindings, 0, 0);
 
     let (half_width, half_height) = {
@@ -53,7 +78,7 @@ pub fn key_selected(
         (w as i32 / 2, h as i32 / 2)
     };
 
-    let box_width = 22;
+    let box_width = 48;
     let box_height = 10;
 
     draw_all_keys(configs, ctx, current_option);
@@ -73,31 +98,90 @@ pub fn key_selected(
         RGB::named(colors::BACKGROUND),
         "Press A Key",
     );
+    ctx.print_color_centered(
+        half_height - box_height / 2 - 2,
+        RGB::named(colors::FOREGROUND),
+        RGB::named(colors::BACKGROUND),
+        "A key bound here is removed",
+    );
 
-    if BAD_KEY_CHOSEN.load(Ordering::Relaxed) {
+    let cancel_key = configs
+        .keys
+        .go_back
+        .first()
+        .map_or_else(String::new, |key| binding_to_string(*key));
+    ctx.print_color_centered(
+        half_height - box_height / 2 - 1,
+        RGB::named(colors::FOREGROUND),
+        RGB::named(colors::BACKGROUND),
+        format!("{cancel_key} cancels"),
+    );
+
+    if let Some(current) = warning {
+        let (first_line, second_line) = match current {
+            Warning::Conflict(key, other) => (
+                format!("{} is bound to {}.", binding_to_string(key), other.as_ref()),
+                "Press it again to move it.".to_string(),
+            ),
+            Warning::OnlyKey(key, other) => (
+                format!("{} is the only key", binding_to_string(key)),
+                format!("bound to {}.", other.as_ref()),
+            ),
+        };
         ctx.print_color_centered(
             half_height - 4,
             RGB::named(rltk::RED),
             RGB::named(colors::BACKGROUND),
-            "Key already assigned.",
+            first_line,
         );
         ctx.print_color_centered(
             half_height - 3,
             RGB::named(rltk::RED),
             RGB::named(colors::BACKGROUND),
-            "Please try again.",
+            second_line,
         );
     }
 
-    if let Some(key) = ctx.key {
-        if KeyBindingOption::iter().all(|option| *option_to_config(configs, option) != key) {
-            *option_to_config(configs, current_option) = key;
-            BAD_KEY_CHOSEN.store(false, Ordering::Relaxed);
-            return true;
+    let Some(key) = KeyBinding::pressed(ctx).filter(|key| !key.is_modifier()) else {
+        return KeySelectResult::NoResponse;
+    };
+    if configs.keys.go_back.contains(&key) {
+        return KeySelectResult::Cancel;
+    }
+    let Some(bindings) = option_to_config(configs, current_option) else {
+        return KeySelectResult::Cancel;
+    };
+
+    //Pressing a key that is already bound here takes it away again
+    if let Some(position) = bindings.iter().position(|binding| *binding == key) {
+        if bindings.len() == 1 {
+            return KeySelectResult::Warned(Warning::OnlyKey(key, current_option));
+        }
+        bindings.remove(position);
+        return KeySelectResult::Changed;
+    }
+
+    let conflict = KeyBindingOption::iter().find(|option| {
+        option_to_config(configs, *option).is_some_and(|bindings| bindings.contains(&key))
+    });
+    if let Some(other) = conflict {
+        let other_bindings = option_to_config(configs, other).unwrap();
+        if other_bindings.len() == 1 {
+            return KeySelectResult::Warned(Warning::OnlyKey(key, other));
         }
-        BAD_KEY_CHOSEN.store(true, Ordering::Relaxed);
+        if warning != Some(Warning::Conflict(key, other)) {
+            return KeySelectResult::Warned(Warning::Conflict(key, other));
+        }
+        other_bindings.retain(|binding| *binding != key);
+    }
+
+    //The oldest binding makes room once the action has as many keys as it can have
+    let bindings = option_to_config(configs, current_option).unwrap();
+    if bindings.len() >= MAX_BINDINGS {
+        bindings.remove(0);
     }
-    false
+    bindings.push(key);
+    KeySelectResult::Changed
 }
 
 fn draw_all_keys(configs: &mut Config, ctx: &mut Rltk, current_option: KeyBindingOption) {
@@ -107,37 +191,71 @@ fn draw_all_keys(configs: &mut Config, ctx: &mut Rltk, current_option: KeyBindin
     let base_y = 5;
     let x = 26;
 
+    //The sprite only draws the lines of the tree, so every label is printed here
     for (i, option) in KeyBindingOption::iter().enumerate() {
         if option != KeyBindingOption::Back {
             let y = base_y + 2 * i;
-            if current_option == option {
-                ctx.print_color(x, y, yellow, bg, current_option.as_ref());
+            let label_color = if current_option == option {
+                yellow
+            } else {
+                RGB::named(colors::FOREGROUND)
+            };
+            ctx.print_color(x, y, label_color, bg, option.as_ref());
+            if let Some(bindings) = option_to_config(configs, option) {
+                let keys = bindings
+                    .iter()
+                    .map(|binding| binding_to_string(*binding))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ctx.print_color(KEYS_X, y, RGB::named(colors::FOREGROUND), bg, keys);
             }
-            let key = vlc_to_str(*option_to_config(configs, option));
-            ctx.print_color(42, y, RGB::named(colors::FOREGROUND), bg, key);
         }
     }
 }
 
-fn option_to_config(configs: &mut Config, current_option: KeyBindingOption) -> &mut VirtualKeyCode {
-    match current_option {
-        KeyBindingOption::Right => &mut configs.keys.move_right,
-        KeyBindingOption::Left => &mut configs.keys.move_left,
-        KeyBindingOption::Up => &mut configs.keys.move_up,
-        KeyBindingOption::Down => &mut configs.keys.move_down,
-        KeyBindingOption::UpRight => &mut configs.keys.move_up_right,
-        KeyBindingOption::UpLeft => &mut configs.keys.move_up_left,
-        KeyBindingOption::DownRight => &mut configs.keys.move_down_right,
-        KeyBindingOption::DownLeft => &mut configs.keys.move_down_left,
-        KeyBindingOption::Descend => &mut configs.keys.descend,
-        KeyBindingOption::Inventory => &mut configs.keys.open_inventory,
-        KeyBindingOption::GrabItem => &mut configs.keys.grab_item,
-        KeyBindingOption::DropItem => &mut configs.keys.drop_item,
-        KeyBindingOption::RemoveItem => &mut configs.keys.remove_item,
-        KeyBindingOption::WaitTurn => &mut configs.keys.wait_turn,
-        KeyBindingOption::Select => &mut configs.keys.select,
-        KeyBindingOption::Back | KeyBindingOption::GoBack => &mut configs.keys.go_back,
+fn option_to_config(
+    configs: &mut Config,
+    current_option: KeyBindingOption,
+) -> Option<&mut Vec<KeyBinding>> {
+    let keys = &mut configs.keys;
+    Some(match current_option {
+        KeyBindingOption::Right => &mut keys.move_right,
+        KeyBindingOption::Left => &mut keys.move_left,
+        KeyBindingOption::Up => &mut keys.move_up,
+        KeyBindingOption::Down => &mut keys.move_down,
+        KeyBindingOption::UpRight => &mut keys.move_up_right,
+        KeyBindingOption::UpLeft => &mut keys.move_up_left,
+        KeyBindingOption::DownRight => &mut keys.move_down_right,
+        KeyBindingOption::DownLeft => &mut keys.move_down_left,
+        KeyBindingOption::Descend => &mut keys.descend,
+        KeyBindingOption::Inventory => &mut keys.open_inventory,
+        KeyBindingOption::Equipment => &mut keys.open_equipment,
+        KeyBindingOption::GrabItem => &mut keys.grab_item,
+        KeyBindingOption::DropItem => &mut keys.drop_item,
+        KeyBindingOption::RemoveItem => &mut keys.remove_item,
+        KeyBindingOption::WaitTurn => &mut keys.wait_turn,
+        KeyBindingOption::Rest => &mut keys.rest,
+        KeyBindingOption::DisarmTrap => &mut keys.disarm_trap,
+        KeyBindingOption::Search => &mut keys.search,
+        KeyBindingOption::Look => &mut keys.look,
+        KeyBindingOption::CycleTarget => &mut keys.cycle_target,
+        KeyBindingOption::AutoExplore => &mut keys.auto_explore,
+        KeyBindingOption::TravelToStairs => &mut keys.travel_to_stairs,
+        KeyBindingOption::Select => &mut keys.select,
+        KeyBindingOption::GoBack => &mut keys.go_back,
+        KeyBindingOption::ResetDefaults | KeyBindingOption::Back => return None,
+    })
+}
+
+fn binding_to_string(binding: KeyBinding) -> String {
+    let mut name = String::new();
+    if binding.control {
+        name.push_str("Ctrl+");
+    }
+    if binding.shift {
+        name.push_str("Shift+");
     }
+    name + vlc_to_str(binding.key)
 }
 
 const fn vlc_to_str(vlc: VirtualKeyCode) -> &'static str {
@@ -4,7 +4,7 @@ pub mod visual;
 
 use crate::{
     constants::{colors, consoles},
-    raws::config::Config,
+    raws::config::{Config, KeyBinding},
     rex_assets,
     state::SettingsOption,
 };
@@ -43,14 +43,14 @@ pub fn show_settings_menu(
 
     let keys = &configs.keys;
 
-    if let Some(key) = ctx.key {
-        if key == keys.select {
+    if let Some(key) = KeyBinding::pressed(ctx) {
+        if keys.select.contains(&key) {
             return (current_state, true);
-        } else if key == keys.go_back {
+        } else if keys.go_back.contains(&key) {
             return (SettingsOption::Back, true);
-        } else if key == keys.move_up {
+        } else if keys.move_up.contains(&key) {
             return (current_state.up(), false);
-        } else if key == keys.move_down {
+        } else if keys.move_down.contains(&key) {
             return (current_state.down(), false);
         }
     }
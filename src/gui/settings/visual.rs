@@ -1,6 +1,6 @@
 use crate::{
     constants::{colors, consoles},
-    raws::config::Config,
+    raws::config::{Config, KeyBinding},
     rex_assets::RexAssets,
     state::VisualOption,
 };
@@ -91,17 +91,17 @@ pub fn show(
 
     let keys = &configs.keys;
 
-    if let Some(key) = ctx.key {
-        if key == keys.go_back {
+    if let Some(key) = KeyBinding::pressed(ctx) {
+        if keys.go_back.contains(&key) {
             return VisualOption::Back;
-        } else if key == keys.move_up {
+        } else if keys.move_up.contains(&key) {
             return current_option.up();
-        } else if key == keys.move_down {
+        } else if keys.move_down.contains(&key) {
             return current_option.down();
         }
 
-        left = key == keys.move_left;
-        right = key == keys.move_right;
+        left = keys.move_left.contains(&key);
+        right = keys.move_right.contains(&key);
     }
 
     match current_option {
@@ -1,24 +1,52 @@
 use crate::{
     camera,
     constants::{colors, consoles},
-    ecs::FieldOfView,
-    raws::config::Config,
+    ecs::{AreaOfEffect, Faction, FieldOfView, Monster, Position},
+    map_builder::map::Map,
+    raws::{
+        config::{Config, KeyBinding},
+        faction::{Reaction, DEFAULT_FACTION, FACTION_RAWS},
+    },
 };
-use rltk::{Point, Rltk, RGB};
-use specs::{Entity, World, WorldExt};
+use rltk::{Algorithm2D, DistanceAlg, Point, Rltk, RGB};
+use specs::{Entity, Join, World, WorldExt};
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum TargetResult {
     Cancel,
     NoResponse,
+    Moved(Point),
     Selected(Point),
 }
 
-pub fn show(configs: &Config, world: &World, ctx: &mut Rltk, range: i32) -> TargetResult {
+///The hostile the player last targeted, which the cursor starts on while it is still in reach
+#[derive(Default)]
+pub struct LastTarget {
+    pub entity: Option<Entity>,
+}
+
+///Where the cursor starts when targeting: the last target, or else the nearest hostile in range
+pub fn initial_cursor(world: &World, range: i32) -> Point {
+    let hostiles = hostiles_in_range(world, range);
+    let last_target = world.fetch::<LastTarget>().entity;
+    last_target
+        .and_then(|target| hostiles.iter().find(|(ent, _)| *ent == target))
+        .or_else(|| hostiles.first())
+        .map_or_else(|| *world.fetch::<Point>(), |(_, pos)| *pos)
+}
+
+pub fn show(
+    configs: &Config,
+    world: &World,
+    ctx: &mut Rltk,
+    range: i32,
+    item: Entity,
+    cursor: Point,
+) -> TargetResult {
     let player_ent = world.fetch::<Entity>();
     let player_pos = world.fetch::<Point>();
     let views = world.read_storage::<FieldOfView>();
-    let (min_x, max_x, min_y, max_y) = camera::get_screen_bounds(world);
+    let (min_x, _, min_y, _) = camera::get_screen_bounds(world);
 
     ctx.set_active_console(consoles::MAP_CONSOLE);
 
@@ -37,11 +65,7 @@ pub fn show(configs: &Config, world: &World, ctx: &mut Rltk, range: i32) -> Targ
             if distance < range as f32 {
                 let screen_x = idx.x - min_x;
                 let screen_y = idx.y - min_y;
-                if screen_x > 1
-                    && screen_x < max_x - min_x - 1
-                    && screen_y > 1
-                    && screen_y < max_y - min_y - 1
-                {
+                if camera::is_on_screen(world, Point::new(screen_x, screen_y)) {
                     ctx.set_bg(screen_x, screen_y, RGB::named(rltk::BLUE));
                     available_cells.push(idx);
                 }
@@ -51,25 +75,123 @@ pub fn show(configs: &Config, world: &World, ctx: &mut Rltk, range: i32) -> Targ
         return TargetResult::Cancel;
     }
 
+    //Preview the blast of area of effect items around the cursor
+    if let Some(area) = world.read_storage::<AreaOfEffect>().get(item) {
+        let map = world.fetch::<Map>();
+        for tile in rltk::field_of_view(cursor, area.radius, &*map) {
+            let (screen_x, screen_y) = (tile.x - min_x, tile.y - min_y);
+            if map.in_bounds(tile) && camera::is_on_screen(world, Point::new(screen_x, screen_y)) {
+                ctx.set_bg(screen_x, screen_y, RGB::named(rltk::ORANGE));
+            }
+        }
+    }
+
     //Draw Cursor
+    let is_valid = |target: Point| available_cells.iter().any(|tile| **tile == target);
+    let cursor_color = if is_valid(cursor) {
+        RGB::named(rltk::CYAN)
+    } else {
+        RGB::named(rltk::RED)
+    };
+    ctx.set_bg(cursor.x - min_x, cursor.y - min_y, cursor_color);
+
     let true_mouse_pos = ctx.mouse_pos();
-    let mouse_pos = { (true_mouse_pos.0 + min_x, true_mouse_pos.1 + min_y) };
+    let mouse_pos = Point::new(true_mouse_pos.0 + min_x, true_mouse_pos.1 + min_y);
     if ctx.left_click {
-        return if available_cells
-            .iter()
-            .any(|tile| tile.x == mouse_pos.0 && tile.y == mouse_pos.1)
-        {
+        return if is_valid(mouse_pos) {
             ctx.set_bg(true_mouse_pos.0, true_mouse_pos.1, RGB::named(rltk::CYAN));
-            TargetResult::Selected(Point::new(mouse_pos.0, mouse_pos.1))
+            select(world, mouse_pos)
         } else {
             ctx.set_bg(true_mouse_pos.0, true_mouse_pos.1, RGB::named(rltk::RED));
             TargetResult::Cancel
         };
     }
 
-    if Some(configs.keys.go_back) == ctx.key {
+    let keys = &configs.keys;
+    let Some(key) = KeyBinding::pressed(ctx) else {
+        return TargetResult::NoResponse;
+    };
+    if keys.go_back.contains(&key) {
         return TargetResult::Cancel;
+    } else if keys.select.contains(&key) {
+        return if is_valid(cursor) {
+            select(world, cursor)
+        } else {
+            TargetResult::NoResponse
+        };
+    } else if keys.cycle_target.contains(&key) {
+        return next_hostile(world, range, cursor)
+            .map_or(TargetResult::NoResponse, TargetResult::Moved);
     }
+    let Some((dx, dy)) = keys.direction(key) else {
+        return TargetResult::NoResponse;
+    };
+
+    let moved = Point::new(cursor.x + dx, cursor.y + dy);
+    if camera::is_on_screen(world, Point::new(moved.x - min_x, moved.y - min_y)) {
+        TargetResult::Moved(moved)
+    } else {
+        TargetResult::NoResponse
+    }
+}
+
+///Remembers the hostile at `target`, if there is one, for the next time the player targets
+fn select(world: &World, target: Point) -> TargetResult {
+    let positions = world.read_storage::<Position>();
+    let entities = world.entities();
+    world.fetch_mut::<LastTarget>().entity = (&entities, &positions)
+        .join()
+        .find(|(ent, pos)| pos.x == target.x && pos.y == target.y && is_hostile(world, *ent))
+        .map(|(ent, _)| ent);
+    TargetResult::Selected(target)
+}
+
+///The hostile after the one under the cursor, going from nearest to farthest
+fn next_hostile(world: &World, range: i32, cursor: Point) -> Option<Point> {
+    let hostiles = hostiles_in_range(world, range);
+    let next = hostiles
+        .iter()
+        .position(|(_, pos)| *pos == cursor)
+        .map_or(0, |current| (current + 1) % hostiles.len());
+    hostiles.get(next).map(|(_, pos)| *pos)
+}
+
+///Every hostile the player can see within `range`, sorted by distance
+fn hostiles_in_range(world: &World, range: i32) -> Vec<(Entity, Point)> {
+    let player_ent = world.fetch::<Entity>();
+    let player_pos = *world.fetch::<Point>();
+    let entities = world.entities();
+    let positions = world.read_storage::<Position>();
+    let views = world.read_storage::<FieldOfView>();
+    let Some(view) = views.get(*player_ent) else {
+        return Vec::new();
+    };
+
+    let mut hostiles = (&entities, &positions)
+        .join()
+        .map(|(ent, pos)| (ent, Point::new(pos.x, pos.y)))
+        .filter(|(ent, pos)| {
+            *ent != *player_ent
+                && DistanceAlg::Pythagoras.distance2d(player_pos, *pos) < range as f32
+                && view.visible_tiles.contains(pos)
+                && is_hostile(world, *ent)
+        })
+        .collect::<Vec<_>>();
+    hostiles.sort_by(|(_, a), (_, b)| {
+        let distance_a = DistanceAlg::Pythagoras.distance2d(player_pos, *a);
+        let distance_b = DistanceAlg::Pythagoras.distance2d(player_pos, *b);
+        distance_a.total_cmp(&distance_b)
+    });
+    hostiles
+}
 
-    TargetResult::NoResponse
+///Whether `ent` belongs to a faction that attacks the player on sight
+pub fn is_hostile(world: &World, ent: Entity) -> bool {
+    let factions = world.read_storage::<Faction>();
+    let faction = match factions.get(ent) {
+        Some(faction) => faction.name.as_str(),
+        None if world.read_storage::<Monster>().get(ent).is_some() => DEFAULT_FACTION,
+        None => return false,
+    };
+    FACTION_RAWS.lock().unwrap().reaction(faction, "Player") == Reaction::Attack
 }
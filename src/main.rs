@@ -32,15 +32,20 @@ mod save_load_util;
 mod spawning;
 mod specs_helpers;
 mod state;
+mod travel;
 
 use constants::consoles;
-use ecs::*;
+use ecs::{identification::Identities, *};
 use game_log::GameLog;
 use gui::{
-    inventory::{InvMode, InvResult},
+    context_menu::ContextResult,
+    equipment::EquipResult,
+    inventory::{AmountResult, InvMode, InvResult},
+    look::LookResult,
+    settings::keybindings::{KeySelectResult, Warning},
     targeting::TargetResult,
 };
-use map_builder::map::Map;
+use map_builder::map::{Map, TileStatus};
 use player::respond_to_input;
 use state::{
     AudioOption, Gameplay,
@@ -81,8 +86,8 @@ impl BashingBytes {
         to_delete
     }
 
-    /// Generates next level for the player to explore
-    fn goto_next_level(&mut self) {
+    /// Replaces the current level with a new one of the given depth
+    fn goto_level(&mut self, depth: i32) {
         let to_delete = self.entities_to_remove_on_level_change();
         for target in to_delete {
             self.world
@@ -92,8 +97,13 @@ impl BashingBytes {
         self.world.maintain();
 
         //Build new map and place player
+        self.generate_world_map(depth);
+    }
+
+    /// Generates next level for the player to explore
+    fn goto_next_level(&mut self) {
         let current_depth = self.world.fetch::<Map>().depth;
-        self.generate_world_map(current_depth + 1);
+        self.goto_level(current_depth + 1);
 
         //Notify player and heal player
         self.world
@@ -116,6 +126,7 @@ impl BashingBytes {
             logs.clear();
             logs.push(&"Welcome to my Roguelike!");
         }
+        self.world.insert(Identities::new_run());
 
         // Create new player resource
         let player_ent = spawning::spawn_player(&mut self.world, 0, 0);
@@ -135,6 +146,7 @@ impl BashingBytes {
         builder.build_map();
         self.world.insert(builder.get_map());
         builder.spawn_entities(&mut self.world);
+        spawning::spawn_depth_specials(&mut self.world);
 
         // Updates the players position based on the new map generated
         // Also must update the player component, and the player pos resource
@@ -244,75 +256,201 @@ impl BashingBytes {
                         State::Menu(Menu::Settings(SettingsOption::Keybindings))
                     }
                     (new_opt, false) => State::Menu(Menu::Keybinding(new_opt)),
-                    (new_opt, true) => State::Menu(Menu::KeySelect(new_opt)),
+                    (new_opt, true) => State::Menu(Menu::KeySelect(new_opt, None)),
                 }
             }
-            Menu::KeySelect(option) => {
-                let assets = &*self.world.fetch::<rex_assets::RexAssets>();
-                if gui::settings::keybindings::key_selected(&mut self.configs, ctx, option, assets)
+            Menu::KeySelect(option, warning) => self.calc_key_select_state(ctx, option, warning),
+        }
+    }
+
+    fn calc_key_select_state(
+        &mut self,
+        ctx: &mut Rltk,
+        option: KeyBindingOption,
+        warning: Option<Warning>,
+    ) -> State {
+        let assets = &*self.world.fetch::<rex_assets::RexAssets>();
+        match gui::settings::keybindings::key_selected(
+            &mut self.configs,
+            ctx,
+            option,
+            warning,
+            assets,
+        ) {
+            KeySelectResult::Cancel | KeySelectResult::Changed => {
+                State::Menu(Menu::Keybinding(option))
+            }
+            KeySelectResult::NoResponse => State::Menu(Menu::KeySelect(option, warning)),
+            KeySelectResult::Warned(warning) => State::Menu(Menu::KeySelect(option, Some(warning))),
+        }
+    }
+
+    fn calc_inventory_state(&mut self, ctx: &mut Rltk, mode: InvMode, page: usize) -> State {
+        match gui::inventory::show(&self.configs, &mut self.world, ctx, page) {
+            InvResult::Cancel => State::Game(Gameplay::AwaitingInput),
+            InvResult::NoResponse => State::Game(Gameplay::Inventory(mode, page)),
+            InvResult::Page(page) => State::Game(Gameplay::Inventory(mode, page)),
+            InvResult::Selected(item) => match mode {
+                //Empty items are turned down before they cost the player a turn
+                InvMode::Use
+                    if is_out_of_charges(&self.world, *self.world.fetch::<Entity>(), item) =>
                 {
-                    State::Menu(Menu::Keybinding(option))
-                } else {
-                    State::Menu(Menu::KeySelect(option))
+                    State::Game(Gameplay::Inventory(mode, page))
+                }
+                InvMode::Use => self.world.read_storage::<Range>().get(item).map_or_else(
+                    || {
+                        let mut intent = self.world.write_storage::<WantsToUseItem>();
+                        intent
+                            .insert(
+                                *self.world.fetch::<Entity>(),
+                                WantsToUseItem { item, target: None },
+                            )
+                            .expect("Unable to insert intent");
+                        State::Game(Gameplay::PlayerTurn)
+                    },
+                    |range| {
+                        let cursor = gui::targeting::initial_cursor(&self.world, range.range);
+                        State::Game(Gameplay::ShowTargeting(range.range, item, cursor))
+                    },
+                ),
+                InvMode::Drop => {
+                    let quantity = self
+                        .world
+                        .read_storage::<Stackable>()
+                        .get(item)
+                        .map_or(1, |stack| stack.quantity);
+                    if quantity > 1 {
+                        State::Game(Gameplay::DropAmount(item, quantity))
+                    } else {
+                        self.drop_item(item, quantity)
+                    }
                 }
+                InvMode::Remove => {
+                    let mut intent = self.world.write_storage::<WantsToRemoveItem>();
+                    intent
+                        .insert(*self.world.fetch::<Entity>(), WantsToRemoveItem { item })
+                        .expect("Unable to insert intent to remove item");
+                    State::Game(Gameplay::PlayerTurn)
+                }
+            },
+        }
+    }
+
+    fn drop_item(&self, item: Entity, quantity: i32) -> State {
+        let mut intent = self.world.write_storage::<WantsToDropItem>();
+        intent
+            .insert(
+                *self.world.fetch::<Entity>(),
+                WantsToDropItem { item, quantity },
+            )
+            .expect("Unable to insert intent to drop item");
+        State::Game(Gameplay::PlayerTurn)
+    }
+
+    fn calc_context_menu_state(&mut self, ctx: &mut Rltk, target: Point) -> State {
+        match gui::context_menu::show(&self.configs, &self.world, ctx, target) {
+            ContextResult::Cancel => State::Game(Gameplay::AwaitingInput),
+            ContextResult::NoResponse => State::Game(Gameplay::ContextMenu(target)),
+            ContextResult::Selected(action) => {
+                State::Game(player::perform(&mut self.world, action, target))
             }
         }
     }
 
+    fn calc_equipment_state(&self, ctx: &mut Rltk, slot: Option<EquipmentSlot>) -> State {
+        let result = match slot {
+            None => gui::equipment::show(&self.configs, &self.world, ctx),
+            Some(slot) => gui::equipment::show_slot(&self.configs, &self.world, ctx, slot),
+        };
+        match (result, slot) {
+            (EquipResult::Cancel, None) => State::Game(Gameplay::AwaitingInput),
+            (EquipResult::Cancel, Some(_)) => State::Game(Gameplay::Equipment(None)),
+            (EquipResult::NoResponse, _) => State::Game(Gameplay::Equipment(slot)),
+            (EquipResult::SelectedSlot(slot), _) => State::Game(Gameplay::Equipment(Some(slot))),
+            (EquipResult::Equip(item), _) => {
+                let mut intent = self.world.write_storage::<WantsToUseItem>();
+                intent
+                    .insert(
+                        *self.world.fetch::<Entity>(),
+                        WantsToUseItem { item, target: None },
+                    )
+                    .expect("Unable to insert intent to equip item");
+                State::Game(Gameplay::PlayerTurn)
+            }
+            (EquipResult::Remove(item), _) => {
+                let mut intent = self.world.write_storage::<WantsToRemoveItem>();
+                intent
+                    .insert(*self.world.fetch::<Entity>(), WantsToRemoveItem { item })
+                    .expect("Unable to insert intent to remove item");
+                State::Game(Gameplay::PlayerTurn)
+            }
+        }
+    }
+
+    ///Reveals the map one row per frame, after which the monsters get their turn
+    fn reveal_map_row(&self, row: i32) -> State {
+        let mut map = self.world.fetch_mut::<Map>();
+        for x in 0..map.width {
+            let idx = map.xy_idx(x, row);
+            map.set_tile_status(idx, TileStatus::Revealed);
+        }
+
+        if row + 1 < map.height {
+            State::Game(Gameplay::RevealMap(row + 1))
+        } else {
+            State::Game(Gameplay::MonsterTurn)
+        }
+    }
+
     fn calc_game_state(&mut self, ctx: &mut Rltk, current_state: Gameplay) -> State {
         match current_state {
             Gameplay::PreRun => {
                 ecs::pre_run_systems::execute(&mut self.world);
                 State::Game(Gameplay::AwaitingInput)
             }
-            Gameplay::AwaitingInput => State::Game(respond_to_input(self, ctx)),
+            Gameplay::AwaitingInput => {
+                gui::look::show_tooltip(&self.world, ctx);
+                State::Game(respond_to_input(self, ctx))
+            }
             Gameplay::PlayerTurn => {
                 ecs::all_systems::execute(&mut self.world);
                 State::Game(Gameplay::MonsterTurn)
             }
             Gameplay::MonsterTurn => {
                 ecs::all_systems::execute(&mut self.world);
-                State::Game(Gameplay::AwaitingInput)
+                State::Game(travel::after_turn(&self.world))
             }
-            Gameplay::Inventory(mode) => {
-                match gui::inventory::show(&self.configs, &mut self.world, ctx) {
-                    InvResult::Cancel => State::Game(Gameplay::AwaitingInput),
-                    InvResult::NoResponse => State::Game(current_state),
-                    InvResult::Selected(item) => match mode {
-                        InvMode::Use => self.world.read_storage::<Range>().get(item).map_or_else(
-                            || {
-                                let mut intent = self.world.write_storage::<WantsToUseItem>();
-                                intent
-                                    .insert(
-                                        *self.world.fetch::<Entity>(),
-                                        WantsToUseItem { item, target: None },
-                                    )
-                                    .expect("Unable to insert intent");
-                                State::Game(Gameplay::PlayerTurn)
-                            },
-                            |range| State::Game(Gameplay::ShowTargeting(range.range, item)),
-                        ),
-                        InvMode::Drop => {
-                            let mut intent = self.world.write_storage::<WantsToDropItem>();
-                            intent
-                                .insert(*self.world.fetch::<Entity>(), WantsToDropItem { item })
-                                .expect("Unable to insert intent to drop item");
-                            State::Game(Gameplay::PlayerTurn)
-                        }
-                        InvMode::Remove => {
-                            let mut intent = self.world.write_storage::<WantsToRemoveItem>();
-                            intent
-                                .insert(*self.world.fetch::<Entity>(), WantsToRemoveItem { item })
-                                .expect("Unable to insert intent to remove item");
-                            State::Game(Gameplay::PlayerTurn)
-                        }
-                    },
+            Gameplay::Travel => State::Game(travel::step(&mut self.world, ctx)),
+            Gameplay::Inventory(mode, page) => self.calc_inventory_state(ctx, mode, page),
+            Gameplay::Equipment(slot) => self.calc_equipment_state(ctx, slot),
+            Gameplay::DropAmount(item, amount) => {
+                match gui::inventory::show_drop_amount(
+                    &self.configs,
+                    &self.world,
+                    ctx,
+                    item,
+                    amount,
+                ) {
+                    AmountResult::Cancel => State::Game(Gameplay::AwaitingInput),
+                    AmountResult::NoResponse => State::Game(current_state),
+                    AmountResult::Changed(amount) => {
+                        State::Game(Gameplay::DropAmount(item, amount))
+                    }
+                    AmountResult::Selected(amount) => self.drop_item(item, amount),
                 }
             }
             Gameplay::NextLevel => {
                 self.goto_next_level();
                 State::Game(Gameplay::PreRun)
             }
+            Gameplay::TownPortal => {
+                self.goto_level(1);
+                self.world
+                    .fetch_mut::<GameLog>()
+                    .push(&"You step through the portal and return to the surface.");
+                State::Game(Gameplay::PreRun)
+            }
+            Gameplay::RevealMap(row) => self.reveal_map_row(row),
             Gameplay::SaveGame => {
                 save_load_util::save_game(&mut self.world);
                 State::Menu(Menu::Main(MainOption::LoadGame))
@@ -325,10 +463,29 @@ impl BashingBytes {
                     State::Menu(Menu::Main(MainOption::NewGame))
                 }
             }
-            Gameplay::ShowTargeting(range, item) => {
-                match gui::targeting::show(&self.configs, &self.world, ctx, range) {
+            Gameplay::Victory => {
+                if gui::victory::show(ctx) {
+                    State::Game(current_state)
+                } else {
+                    self.game_over_cleanup();
+                    State::Menu(Menu::Main(MainOption::NewGame))
+                }
+            }
+            Gameplay::Look(cursor) => {
+                match gui::look::show(&self.configs, &self.world, ctx, cursor) {
+                    LookResult::Cancel => State::Game(Gameplay::AwaitingInput),
+                    LookResult::NoResponse => State::Game(current_state),
+                    LookResult::Moved(cursor) => State::Game(Gameplay::Look(cursor)),
+                }
+            }
+            Gameplay::ContextMenu(target) => self.calc_context_menu_state(ctx, target),
+            Gameplay::ShowTargeting(range, item, cursor) => {
+                match gui::targeting::show(&self.configs, &self.world, ctx, range, item, cursor) {
                     TargetResult::NoResponse => State::Game(current_state),
                     TargetResult::Cancel => State::Game(Gameplay::AwaitingInput),
+                    TargetResult::Moved(cursor) => {
+                        State::Game(Gameplay::ShowTargeting(range, item, cursor))
+                    }
                     TargetResult::Selected(target) => {
                         let mut intent = self.world.write_storage::<WantsToUseItem>();
                         intent
@@ -355,6 +512,7 @@ impl GameState for BashingBytes {
             ctx.cls();
         }
 
+        gui::context_menu::track_right_click(&self.world);
         let current_state = *self.world.fetch::<State>();
 
         let next_state: State = match current_state {
@@ -369,7 +527,14 @@ impl GameState for BashingBytes {
 
                 ecs::cull_dead_characters(&mut self.world);
 
-                state
+                //Systems end the game by replacing the state, which takes priority. Without this the
+                //GameOver written by `cull_dead_characters` was overwritten by the computed state.
+                let system_state = *self.world.fetch::<State>();
+                if system_state == current_state {
+                    state
+                } else {
+                    system_state
+                }
             }
         };
 
@@ -386,11 +551,14 @@ fn main() -> BError {
     const TILE_SIZE: usize = 8;
 
     // todo: Inform player about error loading configs
-    let configs = raws::config::load().map_or_else(|err| err, |ok| ok);
+    let configs = raws::config::load().unwrap_or_else(|err| *err);
 
     // todo: This should not be keeping a global state, but passing the raw spawns
     //  to be used as either a resource, or a part of BashingBytes struct
-    raws::spawn::load();
+    if let Err(err) = raws::spawn::load() {
+        return Err(format!("Unable to load spawns: {err}").into());
+    }
+    raws::faction::load();
 
     // This CANNOT be moved to an external function, because these functions spawn a thread in main,
     // which is required because if the thread dies, so does the audio stream
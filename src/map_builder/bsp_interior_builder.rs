@@ -1,5 +1,5 @@
 use super::{
-    common::{connect_rooms_via_corridors, EDGE_BUFFER},
+    common::{add_secret_doors, connect_rooms_via_corridors, EDGE_BUFFER},
     map::{Map, TileType},
     rect::Rect,
     MapBuilder,
@@ -99,6 +99,8 @@ impl MapBuilder for BSPInteriorBuilder {
             x: start.0,
             y: start.1,
         };
+
+        add_secret_doors(&mut self.map, start, &mut rng);
     }
 
     fn spawn_entities(&mut self, ecs: &mut World) {
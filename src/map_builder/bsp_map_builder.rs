@@ -1,5 +1,5 @@
 use super::{
-    common::{apply_room_to_map, connect_rooms_via_corridors, EDGE_BUFFER},
+    common::{add_secret_doors, apply_room_to_map, connect_rooms_via_corridors, EDGE_BUFFER},
     map::{Map, TileType},
     rect::Rect,
     MapBuilder,
@@ -69,6 +69,8 @@ impl MapBuilder for BSPMapBuilder {
             x: start.0,
             y: start.1,
         };
+
+        add_secret_doors(&mut self.map, start, &mut rng);
     }
 
     fn spawn_entities(&mut self, ecs: &mut World) {
@@ -7,6 +7,7 @@ use std::collections::HashMap;
 
 pub const EDGE_BUFFER: i32 = 2;
 const MAX_STEPS: f32 = 200.0;
+const MAX_SECRET_DOORS: i32 = 3;
 
 ///Given a room, it fills the inner part of the with floors.
 pub fn apply_room_to_map(map: &mut Map, room: &Rect) {
@@ -115,3 +116,117 @@ fn draw_corridor(map: &mut Map, x1: i32, y1: i32, x2: i32, y2: i32) {
         map.tiles[idx] = TileType::Floor;
     }
 }
+
+///Turns a few corridor chokepoints into secret doors. A door is only kept if the stairs can still
+///be reached from the start without passing through any secret door.
+pub fn add_secret_doors(map: &mut Map, start: (i32, i32), rng: &mut RandomNumberGenerator) {
+    let start_idx = map.xy_idx(start.0, start.1);
+    let mut candidates = (0..map.tiles.len())
+        .filter(|idx| *idx != start_idx && is_chokepoint(map, *idx))
+        .collect::<Vec<_>>();
+
+    for _ in 0..rng.roll_dice(1, MAX_SECRET_DOORS) {
+        let Some(choice) = rng.random_slice_index(&candidates) else {
+            return;
+        };
+        let idx = candidates.swap_remove(choice);
+        map.tiles[idx] = TileType::SecretDoor;
+        if !is_stairs_reachable(map, start_idx) {
+            map.tiles[idx] = TileType::Floor;
+        }
+    }
+}
+
+///A floor tile with walls on two opposite sides, and open tiles on the other two
+fn is_chokepoint(map: &Map, idx: usize) -> bool {
+    let (x, y) = (idx as i32 % map.width, idx as i32 / map.width);
+    if map.tiles[idx] != TileType::Floor
+        || x < 1
+        || y < 1
+        || x > map.width - 2
+        || y > map.height - 2
+    {
+        return false;
+    }
+
+    let is_wall = |dx: i32, dy: i32| map.tiles[map.xy_idx(x + dx, y + dy)] == TileType::Wall;
+    let horizontal = is_wall(-1, 0) && is_wall(1, 0) && !is_wall(0, -1) && !is_wall(0, 1);
+    let vertical = is_wall(0, -1) && is_wall(0, 1) && !is_wall(-1, 0) && !is_wall(1, 0);
+    horizontal || vertical
+}
+
+///Walks the map from the start, treating secret doors as walls
+fn is_stairs_reachable(map: &Map, start_idx: usize) -> bool {
+    let mut visited = vec![false; map.tiles.len()];
+    let mut open = vec![start_idx];
+    visited[start_idx] = true;
+
+    while let Some(idx) = open.pop() {
+        if map.tiles[idx] == TileType::StairsDown {
+            return true;
+        }
+        let (x, y) = (idx as i32 % map.width, idx as i32 / map.width);
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || ny < 0 || nx >= map.width || ny >= map.height {
+                continue;
+            }
+            let next = map.xy_idx(nx, ny);
+            let is_open = !matches!(map.tiles[next], TileType::Wall | TileType::SecretDoor);
+            if is_open && !visited[next] {
+                visited[next] = true;
+                open.push(next);
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Two rooms joined by a single corridor, with a dead end hanging off the first room
+    const LAYOUT: [&str; 9] = [
+        "############",
+        "#....###...#",
+        "#.@.......>#",
+        "#....###...#",
+        "##.#########",
+        "##.#########",
+        "#...########",
+        "#...########",
+        "############",
+    ];
+
+    #[test]
+    fn secret_doors_never_cut_off_the_stairs() {
+        let mut map = Map::new(LAYOUT[0].len() as i32, LAYOUT.len() as i32, 1);
+        for (y, row) in LAYOUT.iter().enumerate() {
+            for (x, tile) in row.chars().enumerate() {
+                let idx = map.xy_idx(x as i32, y as i32);
+                map.tiles[idx] = match tile {
+                    '#' => TileType::Wall,
+                    '>' => TileType::StairsDown,
+                    _ => TileType::Floor,
+                };
+            }
+        }
+        let start = (2, 2);
+        let start_idx = map.xy_idx(start.0, start.1);
+
+        let mut doors_placed = 0;
+        for seed in 0..50 {
+            let mut map = map.clone();
+            add_secret_doors(&mut map, start, &mut RandomNumberGenerator::seeded(seed));
+            assert!(is_stairs_reachable(&map, start_idx));
+            doors_placed += map
+                .tiles
+                .iter()
+                .filter(|tile| **tile == TileType::SecretDoor)
+                .count();
+        }
+        //The dead end can always be hidden, so some doors must have been kept
+        assert!(doors_placed > 0);
+    }
+}
@@ -1,7 +1,9 @@
-use rltk::{Algorithm2D, BaseMap, Point};
+use rltk::{Algorithm2D, BaseMap, FontCharType, Point, RGB};
 use serde::{Deserialize, Serialize};
 use specs::prelude::*;
 
+const AMBIENT_LIGHT_FALLOFF: f32 = 0.25;
+
 //Tile Statuses
 pub enum TileStatus {
     Revealed = 0,
@@ -11,11 +13,22 @@ pub enum TileStatus {
 
 #[derive(PartialEq, Copy, Clone, Deserialize, Serialize)]
 pub enum TileType {
+    Door,
     Floor,
+    ///Looks and acts like a wall until it is found, after which it becomes a door
+    SecretDoor,
     StairsDown,
     Wall,
 }
 
+///What the player last saw on a tile, which is drawn once the tile is out of view
+#[derive(Copy, Clone, Deserialize, Serialize)]
+pub struct Memory {
+    pub glyph: FontCharType,
+    pub color: RGB,
+    pub is_monster: bool,
+}
+
 #[derive(Clone, Default, Deserialize, Serialize)]
 pub struct Map {
     pub tiles: Vec<TileType>,
@@ -23,10 +36,16 @@ pub struct Map {
     pub width: i32,
     pub height: i32,
     pub depth: i32,
+    #[serde(default)]
+    pub memories: Vec<Option<Memory>>,
 
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     pub tile_content: Vec<Vec<Entity>>,
+    ///How brightly each tile is lit, from 0.0 to 1.0
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    pub light: Vec<f32>,
 }
 
 impl Map {
@@ -34,7 +53,9 @@ impl Map {
         Self {
             tiles: vec![TileType::Wall; (width * height) as usize],
             tile_status: vec![0; (width * height) as usize],
+            memories: vec![None; (width * height) as usize],
             tile_content: vec![Vec::new(); (width * height) as usize],
+            light: vec![0.0; (width * height) as usize],
             width,
             height,
             depth,
@@ -47,7 +68,7 @@ impl Map {
 
     pub fn populate_blocked(&mut self) {
         for idx in 0..self.tiles.len() {
-            if self.tiles[idx] == TileType::Wall {
+            if self.tiles[idx] == TileType::Wall || self.tiles[idx] == TileType::SecretDoor {
                 self.set_tile_status(idx, TileStatus::Blocked);
             } else {
                 self.remove_tile_status(idx, TileStatus::Blocked);
@@ -74,6 +95,28 @@ impl Map {
         self.tile_status[idx] &= !(1 << status as u8);
     }
 
+    ///How brightly tiles are lit without any light source. The surface is fully lit, and every
+    ///level below it is darker than the last.
+    pub fn ambient_light(&self) -> f32 {
+        ((self.depth - 1) as f32)
+            .mul_add(-AMBIENT_LIGHT_FALLOFF, 1.0)
+            .clamp(0.0, 1.0)
+    }
+
+    pub fn is_lit(&self, idx: usize) -> bool {
+        self.light[idx] > 0.0
+    }
+
+    ///Turns the tile into a door if it is a secret door, returning whether it was one
+    pub fn reveal_secret_door(&mut self, idx: usize) -> bool {
+        if self.tiles[idx] != TileType::SecretDoor {
+            return false;
+        }
+        self.tiles[idx] = TileType::Door;
+        self.remove_tile_status(idx, TileStatus::Blocked);
+        true
+    }
+
     fn is_exit_valid(&self, x: i32, y: i32) -> bool {
         if x < 1 || x > self.width - 1 || y < 1 || y > self.height - 1 {
             return false;
@@ -97,8 +140,8 @@ impl BaseMap for Map {
     fn is_opaque(&self, idx: usize) -> bool {
         #[allow(clippy::match_on_vec_items)]
         match self.tiles[idx] {
-            TileType::Wall => true,
-            TileType::StairsDown | TileType::Floor => false,
+            TileType::Wall | TileType::SecretDoor => true,
+            TileType::Door | TileType::StairsDown | TileType::Floor => false,
         }
     }
 
@@ -1,5 +1,5 @@
 use super::{
-    common::{apply_horizontal_tunnel, apply_room_to_map, apply_vertical_tunnel},
+    common::{add_secret_doors, apply_horizontal_tunnel, apply_room_to_map, apply_vertical_tunnel},
     map::{Map, TileType},
     rect, MapBuilder,
 };
@@ -65,6 +65,8 @@ impl MapBuilder for SimpleMapBuilder {
             x: start_pos.0,
             y: start_pos.1,
         };
+
+        add_secret_doors(&mut self.map, start_pos, &mut rng);
     }
 
     fn spawn_entities(&mut self, ecs: &mut World) {
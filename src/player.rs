@@ -1,52 +1,90 @@
 use super::{
     components::{
-        CombatStats, FieldOfView, Item, Monster, Player, Position, WantsToMelee, WantsToPickupItem,
+        CombatStats, EntityMoved, FieldOfView, Hidden, HungerClock, HungerState, Immobilized, Item,
+        Monster, Name, Overburdened, Player, Position, Trap, WantsToMelee, WantsToPickupItem,
     },
     BashingBytes, GameLog,
 };
 use crate::{
-    gui::inventory::InvMode,
+    camera,
+    ecs::{discover_secret_door, ParticleBuilder},
+    gui::{
+        context_menu::{self, MenuAction},
+        inventory::InvMode,
+    },
     map_builder::map::{Map, TileStatus, TileType},
+    raws::config::KeyBinding,
     state::Gameplay,
+    travel::{self, Destination, PathTarget},
 };
-use rltk::{Point, Rltk, VirtualKeyCode};
+use rltk::{Point, RandomNumberGenerator, Rltk, VirtualKeyCode};
 use specs::{Entity, Join, World, WorldExt};
 
 pub fn respond_to_input(game: &mut BashingBytes, ctx: &mut Rltk) -> Gameplay {
+    if let Some(target) = camera::mouse_to_map(&game.world, ctx) {
+        if context_menu::is_right_click(&game.world) {
+            return Gameplay::ContextMenu(target);
+        }
+        if context_menu::is_left_click(ctx) {
+            return context_menu::default_action(&game.world, target)
+                .map_or(Gameplay::AwaitingInput, |action| {
+                    perform(&mut game.world, action, target)
+                });
+        }
+    }
+
     let keys = &game.configs.keys;
-    if let Some(key) = ctx.key {
-        if key == keys.move_up {
+    if let Some(key) = KeyBinding::pressed(ctx) {
+        if keys.move_up.contains(&key) {
             try_move(0, -1, &mut game.world);
-        } else if key == keys.move_down {
+        } else if keys.move_down.contains(&key) {
             try_move(0, 1, &mut game.world);
-        } else if key == keys.move_left {
+        } else if keys.move_left.contains(&key) {
             try_move(-1, 0, &mut game.world);
-        } else if key == keys.move_right {
+        } else if keys.move_right.contains(&key) {
             try_move(1, 0, &mut game.world);
-        } else if key == keys.move_up_left {
+        } else if keys.move_up_left.contains(&key) {
             try_move(-1, -1, &mut game.world);
-        } else if key == keys.move_up_right {
+        } else if keys.move_up_right.contains(&key) {
             try_move(1, -1, &mut game.world);
-        } else if key == keys.move_down_left {
+        } else if keys.move_down_left.contains(&key) {
             try_move(-1, 1, &mut game.world);
-        } else if key == keys.move_down_right {
+        } else if keys.move_down_right.contains(&key) {
             try_move(1, 1, &mut game.world);
-        } else if key == keys.descend {
+        } else if keys.descend.contains(&key) {
             return try_descend(&mut game.world);
-        } else if key == keys.grab_item {
+        } else if keys.grab_item.contains(&key) {
             try_pickup(&mut game.world);
-        } else if key == keys.drop_item {
-            return Gameplay::Inventory(InvMode::Drop);
-        } else if key == keys.remove_item {
-            return Gameplay::Inventory(InvMode::Remove);
-        } else if key == keys.open_inventory {
-            return Gameplay::Inventory(InvMode::Use);
-        } else if key == keys.go_back {
+        } else if keys.drop_item.contains(&key) {
+            return Gameplay::Inventory(InvMode::Drop, 0);
+        } else if keys.remove_item.contains(&key) {
+            return Gameplay::Inventory(InvMode::Remove, 0);
+        } else if keys.open_inventory.contains(&key) {
+            return Gameplay::Inventory(InvMode::Use, 0);
+        } else if keys.open_equipment.contains(&key) {
+            return Gameplay::Equipment(None);
+        } else if keys.go_back.contains(&key) {
             return Gameplay::SaveGame;
-        } else if key == keys.wait_turn {
+        } else if keys.wait_turn.contains(&key) {
             return skip_turn(&mut game.world);
-        } else if key == VirtualKeyCode::M {
-
+        } else if keys.rest.contains(&key) {
+            return travel::start(&game.world, Destination::Rest);
+        } else if keys.disarm_trap.contains(&key) {
+            return try_disarm(&game.world);
+        } else if keys.search.contains(&key) {
+            return search(&game.world);
+        } else if keys.auto_explore.contains(&key) {
+            return travel::start(&game.world, Destination::Path(PathTarget::Unexplored));
+        } else if keys.travel_to_stairs.contains(&key) {
+            return travel::start(&game.world, Destination::Path(PathTarget::Stairs));
+        } else if keys.look.contains(&key) {
+            return Gameplay::Look(*game.world.fetch::<Point>());
+        } else if let Some((delta_x, delta_y)) =
+            keys.direction(key.without_shift()).filter(|_| key.shift)
+        {
+            //Running only applies when shift and the movement key aren't bound to anything else
+            return travel::start(&game.world, Destination::Run(delta_x, delta_y));
+        } else if key.key == VirtualKeyCode::M {
         } else {
             return Gameplay::AwaitingInput;
         }
@@ -57,14 +95,34 @@ pub fn respond_to_input(game: &mut BashingBytes, ctx: &mut Rltk) -> Gameplay {
     Gameplay::PlayerTurn
 }
 
-fn try_move(delta_x: i32, delta_y: i32, ecs: &mut World) {
+///Carries out an action the player picked with the mouse on the tile at `target`
+pub fn perform(ecs: &mut World, action: MenuAction, target: Point) -> Gameplay {
+    let player_pos = *ecs.fetch::<Point>();
+    match action {
+        MenuAction::Look => Gameplay::Look(target),
+        MenuAction::Attack => {
+            try_move(target.x - player_pos.x, target.y - player_pos.y, ecs);
+            Gameplay::PlayerTurn
+        }
+        MenuAction::PickUp => {
+            try_pickup(ecs);
+            Gameplay::PlayerTurn
+        }
+        MenuAction::TravelHere => travel::start(ecs, Destination::Path(PathTarget::Tile(target))),
+    }
+}
+
+pub fn try_move(delta_x: i32, delta_y: i32, ecs: &mut World) {
     let mut positions = ecs.write_storage::<Position>();
     let mut fields_of_view = ecs.write_storage::<FieldOfView>();
     let mut players = ecs.write_storage::<Player>();
     let mut attacks = ecs.write_storage::<WantsToMelee>();
+    let mut immobilized = ecs.write_storage::<Immobilized>();
+    let mut moved = ecs.write_storage::<EntityMoved>();
     let entities = ecs.entities();
 
     let combat_stats = ecs.read_storage::<CombatStats>();
+    let overburdened = ecs.read_storage::<Overburdened>();
     let map = &ecs.fetch::<Map>();
 
     //Allows the player to attack if position is occupied
@@ -96,6 +154,28 @@ fn try_move(delta_x: i32, delta_y: i32, ecs: &mut World) {
             }
         }
 
+        //Being held in place costs the player their step until they break free
+        if let Some(held) = immobilized.get_mut(entity) {
+            held.turns -= 1;
+            let broke_free = held.turns <= 0;
+            if broke_free {
+                immobilized.remove(entity);
+            }
+            ecs.fetch_mut::<GameLog>().push(if broke_free {
+                &"You break free."
+            } else {
+                &"You are held in place."
+            });
+            return;
+        }
+
+        //Being overburdened sometimes costs the player their step
+        if overburdened.get(entity).is_some() && RandomNumberGenerator::new().roll_dice(1, 3) == 1 {
+            ecs.fetch_mut::<GameLog>()
+                .push(&"You stagger under the weight of your pack.");
+            return;
+        }
+
         //If not blocked, moves the player there
         if !map.is_tile_status_set(destination_idx, TileStatus::Blocked) {
             pos.x = std::cmp::min(map.width - 1, std::cmp::max(0, pos.x + delta_x));
@@ -104,8 +184,95 @@ fn try_move(delta_x: i32, delta_y: i32, ecs: &mut World) {
             player_pos.x = pos.x;
             player_pos.y = pos.y;
             fov.is_dirty = true;
+            moved
+                .insert(entity, EntityMoved {})
+                .expect("Unable to mark the player as moved");
+        }
+    }
+}
+
+///Tries to disarm a spotted trap next to or below the player, which succeeds most of the time
+fn try_disarm(ecs: &World) -> Gameplay {
+    let entities = ecs.entities();
+    let traps = ecs.read_storage::<Trap>();
+    let hidden = ecs.read_storage::<Hidden>();
+    let names = ecs.read_storage::<Name>();
+    let positions = ecs.read_storage::<Position>();
+    let player_pos = ecs.fetch::<Point>();
+    let mut logs = ecs.fetch_mut::<GameLog>();
+
+    let target = (&entities, &traps, &positions, !&hidden)
+        .join()
+        .find(|(_, _, pos, ())| {
+            (pos.x - player_pos.x).abs() <= 1 && (pos.y - player_pos.y).abs() <= 1
+        })
+        .map(|(trap, ..)| trap);
+    let Some(trap) = target else {
+        logs.push(&"There is no trap nearby to disarm.");
+        return Gameplay::AwaitingInput;
+    };
+
+    let trap_name = &names.get(trap).unwrap().name;
+    if RandomNumberGenerator::new().roll_dice(1, 4) == 1 {
+        logs.push(&format!("You fail to disarm the {trap_name}."));
+    } else {
+        logs.push(&format!("You disarm the {trap_name}."));
+        entities
+            .delete(trap)
+            .expect("Unable to delete disarmed trap");
+    }
+    Gameplay::PlayerTurn
+}
+
+///Searches the tiles around the player for secret doors and hidden traps, each of which is found
+///most of the time
+fn search(ecs: &World) -> Gameplay {
+    let player_ent = ecs.fetch::<Entity>();
+    let player_pos = ecs.fetch::<Point>();
+    let entities = ecs.entities();
+    let traps = ecs.read_storage::<Trap>();
+    let names = ecs.read_storage::<Name>();
+    let positions = ecs.read_storage::<Position>();
+    let mut hidden = ecs.write_storage::<Hidden>();
+    let mut fovs = ecs.write_storage::<FieldOfView>();
+    let mut map = ecs.fetch_mut::<Map>();
+    let mut logs = ecs.fetch_mut::<GameLog>();
+    let mut particles = ecs.fetch_mut::<ParticleBuilder>();
+    let mut rng = RandomNumberGenerator::new();
+
+    let is_nearby = |x: i32, y: i32| (x - player_pos.x).abs() <= 1 && (y - player_pos.y).abs() <= 1;
+
+    let mut found_door = false;
+    for y in player_pos.y - 1..=player_pos.y + 1 {
+        for x in player_pos.x - 1..=player_pos.x + 1 {
+            if x < 0 || y < 0 || x >= map.width || y >= map.height {
+                continue;
+            }
+            let idx = map.xy_idx(x, y);
+            if map.tiles[idx] == TileType::SecretDoor && rng.roll_dice(1, 3) > 1 {
+                found_door |= discover_secret_door(&mut map, idx, &mut logs, &mut particles);
+            }
+        }
+    }
+
+    let spotted = (&entities, &traps, &positions, &hidden)
+        .join()
+        .filter(|(_, _, pos, _)| is_nearby(pos.x, pos.y))
+        .map(|(trap, ..)| trap)
+        .collect::<Vec<_>>();
+    for trap in spotted {
+        if rng.roll_dice(1, 3) > 1 {
+            hidden.remove(trap);
+            logs.push(&format!("You spot a {}.", names.get(trap).unwrap().name));
+        }
+    }
+
+    if found_door {
+        if let Some(fov) = fovs.get_mut(*player_ent) {
+            fov.is_dirty = true;
         }
     }
+    Gameplay::PlayerTurn
 }
 
 fn try_pickup(ecs: &mut World) {
@@ -153,7 +320,7 @@ fn try_descend(ecs: &mut World) -> Gameplay {
     }
 }
 
-fn skip_turn(ecs: &mut World) -> Gameplay {
+pub fn skip_turn(ecs: &mut World) -> Gameplay {
     let fields_of_view = ecs.read_storage::<FieldOfView>();
     let player_ent = ecs.fetch::<Entity>();
     let player_vs = fields_of_view.get(*player_ent).unwrap();
@@ -169,10 +336,11 @@ fn skip_turn(ecs: &mut World) -> Gameplay {
     };
 
     //If the players fov does not contain mobs they may heal a point by waiting
-    if !player_vs
-        .visible_tiles
-        .iter()
-        .any(|&tile| contains_mob(tile))
+    if !is_hungry(ecs)
+        && !player_vs
+            .visible_tiles
+            .iter()
+            .any(|&tile| contains_mob(tile))
     {
         let mut all_stats = ecs.write_storage::<CombatStats>();
         let player_stats = all_stats.get_mut(*player_ent).unwrap();
@@ -181,3 +349,12 @@ fn skip_turn(ecs: &mut World) -> Gameplay {
 
     Gameplay::PlayerTurn
 }
+
+///Hungry players can't rest
+pub fn is_hungry(ecs: &World) -> bool {
+    ecs.read_storage::<HungerClock>()
+        .get(*ecs.fetch::<Entity>())
+        .is_some_and(|clock| {
+            clock.state == HungerState::Hungry || clock.state == HungerState::Starving
+        })
+}
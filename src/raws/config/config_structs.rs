@@ -1,7 +1,7 @@
 use crate::gui::settings::visual;
-use rltk::VirtualKeyCode;
-use serde::Deserialize;
+use rltk::{Rltk, VirtualKeyCode};
 use serde::Serialize;
+use serde::{Deserialize, Deserializer};
 
 //Helping VirtualKeyCode
 #[derive(Serialize, Deserialize)]
@@ -343,71 +343,222 @@ impl From<VirtualKeyCodeDef> for VirtualKeyCode {
     }
 }
 
+///A key together with the modifiers that have to be held down with it
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeyBinding {
+    #[serde(with = "VirtualKeyCodeDef")]
+    pub key: VirtualKeyCode,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub shift: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub control: bool,
+}
+
+//Serde hands over a reference, so it can't take the bool by value
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl KeyBinding {
+    pub const fn new(key: VirtualKeyCode) -> Self {
+        Self {
+            key,
+            shift: false,
+            control: false,
+        }
+    }
+
+    ///The key the player just pressed, along with the modifiers they held down
+    pub fn pressed(ctx: &Rltk) -> Option<Self> {
+        ctx.key.map(|key| Self {
+            key,
+            shift: ctx.shift,
+            control: ctx.control,
+        })
+    }
+
+    pub const fn without_shift(self) -> Self {
+        Self {
+            shift: false,
+            ..self
+        }
+    }
+
+    ///Modifier keys are reported on their own while being held down for another key
+    pub const fn is_modifier(self) -> bool {
+        matches!(
+            self.key,
+            VirtualKeyCode::LShift
+                | VirtualKeyCode::RShift
+                | VirtualKeyCode::LControl
+                | VirtualKeyCode::RControl
+        )
+    }
+}
+
+fn bindings(keys: &[VirtualKeyCode]) -> Vec<KeyBinding> {
+    keys.iter().copied().map(KeyBinding::new).collect()
+}
+
+///Actions missing from the configs, like ones added since they were saved, keep their default keys
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct KeyBinds {
     //Movement Keys
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub move_up: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub move_down: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub move_left: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub move_right: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub move_up_left: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub move_up_right: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub move_down_left: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub move_down_right: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub descend: VirtualKeyCode,
+    pub move_up: Vec<KeyBinding>,
+    pub move_down: Vec<KeyBinding>,
+    pub move_left: Vec<KeyBinding>,
+    pub move_right: Vec<KeyBinding>,
+    pub move_up_left: Vec<KeyBinding>,
+    pub move_up_right: Vec<KeyBinding>,
+    pub move_down_left: Vec<KeyBinding>,
+    pub move_down_right: Vec<KeyBinding>,
+    pub descend: Vec<KeyBinding>,
 
     //Item Related keys
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub grab_item: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub drop_item: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub remove_item: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub open_inventory: VirtualKeyCode,
+    pub grab_item: Vec<KeyBinding>,
+    pub drop_item: Vec<KeyBinding>,
+    pub remove_item: Vec<KeyBinding>,
+    pub open_inventory: Vec<KeyBinding>,
+    pub open_equipment: Vec<KeyBinding>,
 
     //Other keys
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub go_back: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub wait_turn: VirtualKeyCode,
-    #[serde(with = "VirtualKeyCodeDef")]
-    pub select: VirtualKeyCode,
+    pub go_back: Vec<KeyBinding>,
+    pub wait_turn: Vec<KeyBinding>,
+    pub rest: Vec<KeyBinding>,
+    pub disarm_trap: Vec<KeyBinding>,
+    pub search: Vec<KeyBinding>,
+    pub look: Vec<KeyBinding>,
+    pub cycle_target: Vec<KeyBinding>,
+    pub auto_explore: Vec<KeyBinding>,
+    pub travel_to_stairs: Vec<KeyBinding>,
+    pub select: Vec<KeyBinding>,
 }
 impl Default for KeyBinds {
     fn default() -> Self {
         Self {
             //Movement
-            move_up: VirtualKeyCode::K,
-            move_down: VirtualKeyCode::J,
-            move_left: VirtualKeyCode::H,
-            move_right: VirtualKeyCode::L,
-            move_up_left: VirtualKeyCode::Z,
-            move_up_right: VirtualKeyCode::U,
-            move_down_left: VirtualKeyCode::B,
-            move_down_right: VirtualKeyCode::N,
-            descend: VirtualKeyCode::Period,
+            move_up: bindings(&[
+                VirtualKeyCode::K,
+                VirtualKeyCode::Up,
+                VirtualKeyCode::Numpad8,
+            ]),
+            move_down: bindings(&[
+                VirtualKeyCode::J,
+                VirtualKeyCode::Down,
+                VirtualKeyCode::Numpad2,
+            ]),
+            move_left: bindings(&[
+                VirtualKeyCode::H,
+                VirtualKeyCode::Left,
+                VirtualKeyCode::Numpad4,
+            ]),
+            move_right: bindings(&[
+                VirtualKeyCode::L,
+                VirtualKeyCode::Right,
+                VirtualKeyCode::Numpad6,
+            ]),
+            move_up_left: bindings(&[VirtualKeyCode::Z, VirtualKeyCode::Numpad7]),
+            move_up_right: bindings(&[VirtualKeyCode::U, VirtualKeyCode::Numpad9]),
+            move_down_left: bindings(&[VirtualKeyCode::B, VirtualKeyCode::Numpad1]),
+            move_down_right: bindings(&[VirtualKeyCode::N, VirtualKeyCode::Numpad3]),
+            descend: bindings(&[VirtualKeyCode::Period]),
 
             //Item related
-            grab_item: VirtualKeyCode::G,
-            drop_item: VirtualKeyCode::D,
-            remove_item: VirtualKeyCode::R,
-            open_inventory: VirtualKeyCode::I,
+            grab_item: bindings(&[VirtualKeyCode::G]),
+            drop_item: bindings(&[VirtualKeyCode::D]),
+            remove_item: bindings(&[VirtualKeyCode::R]),
+            open_inventory: bindings(&[VirtualKeyCode::I]),
+            open_equipment: bindings(&[VirtualKeyCode::E]),
 
             //Other
-            go_back: VirtualKeyCode::Escape,
-            wait_turn: VirtualKeyCode::Space,
-            select: VirtualKeyCode::Return,
+            go_back: bindings(&[VirtualKeyCode::Escape]),
+            wait_turn: bindings(&[VirtualKeyCode::Space, VirtualKeyCode::Numpad5]),
+            rest: bindings(&[VirtualKeyCode::W]),
+            disarm_trap: bindings(&[VirtualKeyCode::X]),
+            search: bindings(&[VirtualKeyCode::S]),
+            look: bindings(&[VirtualKeyCode::V]),
+            cycle_target: bindings(&[VirtualKeyCode::Tab]),
+            auto_explore: bindings(&[VirtualKeyCode::O]),
+            travel_to_stairs: bindings(&[VirtualKeyCode::T]),
+            select: bindings(&[VirtualKeyCode::Return, VirtualKeyCode::NumpadEnter]),
+        }
+    }
+}
+
+//Configs saved before an action could have several key bindings hold a single key per action
+macro_rules! single_key_binds {
+    ($($action:ident),* $(,)?) => {
+        #[derive(Deserialize)]
+        pub struct SingleKeyBinds {
+            $(
+            #[serde(default, deserialize_with = "single_key")]
+            $action: Option<KeyBinding>,
+            )*
+        }
+
+        impl From<SingleKeyBinds> for KeyBinds {
+            fn from(saved: SingleKeyBinds) -> Self {
+                let mut keys = Self::default();
+                $(
+                if let Some(binding) = saved.$action {
+                    keys.$action = vec![binding];
+                }
+                )*
+                keys
+            }
         }
+    };
+}
+
+single_key_binds!(
+    move_up,
+    move_down,
+    move_left,
+    move_right,
+    move_up_left,
+    move_up_right,
+    move_down_left,
+    move_down_right,
+    descend,
+    grab_item,
+    drop_item,
+    remove_item,
+    open_inventory,
+    open_equipment,
+    go_back,
+    wait_turn,
+    rest,
+    disarm_trap,
+    search,
+    look,
+    cycle_target,
+    auto_explore,
+    travel_to_stairs,
+    select,
+);
+
+fn single_key<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<KeyBinding>, D::Error> {
+    VirtualKeyCodeDef::deserialize(deserializer).map(|key| Some(KeyBinding::new(key)))
+}
+
+impl KeyBinds {
+    ///The direction `key` moves in, if it is one of the movement keys
+    pub fn direction(&self, key: KeyBinding) -> Option<(i32, i32)> {
+        [
+            (&self.move_up, (0, -1)),
+            (&self.move_down, (0, 1)),
+            (&self.move_left, (-1, 0)),
+            (&self.move_right, (1, 0)),
+            (&self.move_up_left, (-1, -1)),
+            (&self.move_up_right, (1, -1)),
+            (&self.move_down_left, (-1, 1)),
+            (&self.move_down_right, (1, 1)),
+        ]
+        .iter()
+        .find(|(bindings, _)| bindings.contains(&key))
+        .map(|(_, direction)| *direction)
     }
 }
 
@@ -461,3 +612,42 @@ impl Default for AudioConfigs {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_movement_key_moves_its_own_way() {
+        let keys = KeyBinds::default();
+
+        for key in [
+            VirtualKeyCode::K,
+            VirtualKeyCode::Up,
+            VirtualKeyCode::Numpad8,
+        ] {
+            assert_eq!(keys.direction(KeyBinding::new(key)), Some((0, -1)));
+        }
+        assert_eq!(
+            keys.direction(KeyBinding::new(VirtualKeyCode::L)),
+            Some((1, 0))
+        );
+        assert_eq!(
+            keys.direction(KeyBinding::new(VirtualKeyCode::Numpad1)),
+            Some((-1, 1))
+        );
+    }
+
+    #[test]
+    fn other_keys_do_not_move() {
+        let keys = KeyBinds::default();
+        let shifted_up = KeyBinding {
+            shift: true,
+            ..KeyBinding::new(VirtualKeyCode::K)
+        };
+
+        assert_eq!(keys.direction(KeyBinding::new(VirtualKeyCode::I)), None);
+        assert_eq!(keys.direction(shifted_up), None);
+        assert_eq!(keys.direction(shifted_up.without_shift()), Some((0, -1)));
+    }
+}
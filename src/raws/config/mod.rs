@@ -1,5 +1,6 @@
 mod config_structs;
-use config_structs::{AudioConfigs, KeyBinds, VisualConfigs};
+pub use config_structs::KeyBinding;
+use config_structs::{AudioConfigs, KeyBinds, SingleKeyBinds, VisualConfigs};
 
 use serde::{Deserialize, Serialize};
 
@@ -10,19 +11,44 @@ pub struct Config {
     pub audio: AudioConfigs,
 }
 
+///The configs as saved before an action could have several key bindings
+#[derive(Deserialize)]
+struct SingleKeyConfig {
+    keys: SingleKeyBinds,
+    visual: VisualConfigs,
+    audio: AudioConfigs,
+}
+
+impl From<SingleKeyConfig> for Config {
+    fn from(saved: SingleKeyConfig) -> Self {
+        Self {
+            keys: saved.keys.into(),
+            visual: saved.visual,
+            audio: saved.audio,
+        }
+    }
+}
+
 impl Config {
     pub fn load_config(&mut self, desired_config: Self) {
         *self = desired_config;
     }
 }
 
-pub fn load() -> Result<Config, Config> {
-    let config = include_bytes!("../../../prefabs/config.ron");
+///Reads the saved configs, handing back the defaults as the error if they can't be read
+pub fn load() -> Result<Config, Box<Config>> {
+    parse(include_bytes!("../../../prefabs/config.ron"))
+}
 
-    match ron::de::from_bytes(config) {
-        Ok(config) => Ok(config),
-        Err(_) => Err(Config::default()),
-    }
+fn parse(config: &[u8]) -> Result<Config, Box<Config>> {
+    ron::de::from_bytes(config).or_else(|err| {
+        ron::de::from_bytes::<SingleKeyConfig>(config)
+            .map(Config::from)
+            .map_err(|_| {
+                println!("Unable to read the configs, so the defaults are used instead: {err}");
+                Box::default()
+            })
+    })
 }
 
 pub fn save(current_configs: &Config) -> ron::Result<()> {
@@ -32,3 +58,45 @@ pub fn save(current_configs: &Config) -> ron::Result<()> {
 
     ron::ser::to_writer_pretty(writer, current_configs, pretty)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rltk::VirtualKeyCode;
+
+    #[test]
+    fn configs_with_a_single_key_per_action_still_load() {
+        let saved = br"(
+            keys: (move_up: W, select: Space),
+            visual: (
+                full_screen: false,
+                screen_shake: false,
+                dynamic_color: false,
+                active_font: Default,
+                color_mapping: (
+                    player: (178, 178, 0),
+                    enemy: (140, 0, 35),
+                    collectable: (0, 140, 140),
+                    grass: (70, 140, 0),
+                    water: (0, 70, 140),
+                    deep_water: (0, 0, 140),
+                    lava: (140, 0, 35),
+                ),
+            ),
+            audio: (master_volume: 0, music_volume: 25, sfx_volume: 20),
+        )";
+        let Ok(config) = parse(saved) else {
+            panic!("The configs fell back to the defaults");
+        };
+
+        assert_eq!(config.keys.move_up, [KeyBinding::new(VirtualKeyCode::W)]);
+        assert_eq!(config.keys.select, [KeyBinding::new(VirtualKeyCode::Space)]);
+        assert_eq!(config.keys.look, Config::default().keys.look);
+        assert_eq!(config.audio.music_volume, 25);
+    }
+
+    #[test]
+    fn the_shipped_configs_load() {
+        assert!(load().is_ok());
+    }
+}
@@ -0,0 +1,91 @@
+use super::faction_structs::{RawFaction, Reaction};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+//Used when a faction has no entry for the other faction
+const DEFAULT_KEY: &str = "Default";
+
+#[derive(Deserialize, Debug)]
+pub struct RawData {
+    pub factions: Vec<RawFaction>,
+}
+
+impl RawData {
+    pub const fn new() -> Self {
+        Self {
+            factions: Vec::new(),
+        }
+    }
+}
+
+pub struct FactionMaster {
+    raw_data: RawData,
+    faction_index: HashMap<String, usize>,
+}
+
+impl FactionMaster {
+    pub fn empty() -> Self {
+        Self {
+            raw_data: RawData::new(),
+            faction_index: HashMap::new(),
+        }
+    }
+
+    pub fn load(&mut self, raws: RawData) {
+        self.raw_data = raws;
+
+        for (i, faction) in self.raw_data.factions.iter().enumerate() {
+            self.faction_index.insert(faction.name.clone(), i);
+        }
+    }
+
+    ///Returns how `my_faction` reacts to `their_faction`. Unknown factions are ignored.
+    pub fn reaction(&self, my_faction: &str, their_faction: &str) -> Reaction {
+        self.faction_index
+            .get(my_faction)
+            .map_or(Reaction::Ignore, |&index| {
+                let reactions = &self.raw_data.factions[index].reactions;
+                reactions
+                    .get(their_faction)
+                    .or_else(|| reactions.get(DEFAULT_KEY))
+                    .copied()
+                    .unwrap_or(Reaction::Ignore)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raws::faction::DEFAULT_FACTION;
+
+    #[test]
+    fn reactions_follow_the_raws() {
+        let raws: RawData =
+            ron::de::from_str(include_str!("../../../prefabs/factions.ron")).unwrap();
+        let mut factions = FactionMaster::empty();
+        factions.load(raws);
+
+        assert_eq!(
+            factions.reaction("Goblinoid", "Goblinoid"),
+            Reaction::Ignore
+        );
+        assert_eq!(factions.reaction("Wildlife", "Player"), Reaction::Flee);
+        //Factions not listed get the "Default" reaction
+        assert_eq!(factions.reaction("Goblinoid", "Kobold"), Reaction::Attack);
+        assert_eq!(
+            factions.reaction(DEFAULT_FACTION, "Player"),
+            Reaction::Attack
+        );
+        assert_eq!(
+            factions.reaction(DEFAULT_FACTION, "Vermin"),
+            Reaction::Ignore
+        );
+    }
+
+    #[test]
+    fn unknown_factions_ignore_everyone() {
+        let factions = FactionMaster::empty();
+        assert_eq!(factions.reaction("Goblinoid", "Player"), Reaction::Ignore);
+    }
+}
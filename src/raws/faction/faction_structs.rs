@@ -0,0 +1,15 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Reaction {
+    Attack,
+    Ignore,
+    Flee,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawFaction {
+    pub name: String,
+    pub reactions: HashMap<String, Reaction>,
+}
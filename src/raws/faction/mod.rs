@@ -0,0 +1,33 @@
+mod faction_master;
+mod faction_structs;
+
+use std::sync::Mutex;
+
+pub use faction_master::FactionMaster;
+pub use faction_structs::Reaction;
+
+///The faction of monsters that have none, either in their raws or in a save from before factions
+pub const DEFAULT_FACTION: &str = "Monster";
+
+//See raws::spawn for why this is a macro
+#[rustfmt::skip]
+macro_rules! raw_factions_path {
+    () => ("../../../prefabs/factions.ron")
+}
+
+lazy_static::lazy_static! {
+    pub static ref FACTION_RAWS: Mutex<FactionMaster> = Mutex::new(FactionMaster::empty());
+}
+
+rltk::embedded_resource!(RAW_FACTIONS, raw_factions_path!());
+
+pub fn load() {
+    rltk::link_resource!(RAW_FACTIONS, raw_factions_path!());
+    let faction_raw = rltk::embedding::EMBED
+        .lock()
+        .get_resource(raw_factions_path!().to_string())
+        .unwrap();
+    let decoder: faction_master::RawData =
+        ron::de::from_bytes(faction_raw).expect("Unable to parse RON");
+    FACTION_RAWS.lock().unwrap().load(decoder);
+}
@@ -0,0 +1,37 @@
+use crate::components::EquipmentSlot;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct Rarity {
+    pub name: String,
+    pub weight: i32,
+    #[serde(default)]
+    pub weight_per_depth: i32,
+    pub min_depth: i32,
+    ///How many affixes an item of this rarity has, one prefix and one suffix at most
+    pub affixes: i32,
+    pub color: Option<(u8, u8, u8)>,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Affix {
+    pub name: String,
+    pub kind: AffixKind,
+    pub weight: i32,
+    pub min_depth: i32,
+    ///The slots of the items the affix can roll on, any slot if empty
+    #[serde(default)]
+    pub slots: Vec<EquipmentSlot>,
+    #[serde(default)]
+    pub damage_bonus: i32,
+    #[serde(default)]
+    pub defense_bonus: i32,
+    #[serde(default)]
+    pub item_weight: i32,
+}
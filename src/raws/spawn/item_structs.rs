@@ -1,3 +1,4 @@
+use crate::components::EquipmentSlot;
 use serde::Deserialize;
 use std::collections::HashMap;
 
@@ -6,11 +7,29 @@ pub struct Item {
     pub name: String,
     pub render: RawRender,
     pub consumable: Option<RawConsumable>,
+    pub charged: Option<RawCharged>,
     pub weapon: Option<RawWeapon>,
     pub shield: Option<RawShield>,
+    pub wearable: Option<RawWearable>,
+    pub unidentified: Option<Unidentified>,
+    #[serde(default)]
+    pub artifact: bool,
+    #[serde(default)]
+    pub cursed: bool,
+    #[serde(default)]
+    pub weight: i32,
+    ///The range of the light the item gives off while equipped
+    pub light: Option<i32>,
 }
 
-#[derive(Deserialize, Debug)]
+///Items of a kind are given a random name each run until the player identifies them
+#[derive(Deserialize, Debug, Copy, Clone)]
+pub enum Unidentified {
+    Potion,
+    Scroll,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone)]
 pub struct RawRender {
     pub glyph: u16,
     pub color: (u8, u8, u8),
@@ -22,6 +41,16 @@ pub struct RawConsumable {
     pub effects: HashMap<String, String>,
 }
 
+///Wands and staves keep their effects until their charges run out
+#[derive(Deserialize, Debug)]
+pub struct RawCharged {
+    pub charges: i32,
+    ///Whether the item crumbles once empty, or is kept and becomes unusable
+    #[serde(default)]
+    pub crumbles: bool,
+    pub effects: HashMap<String, String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct RawShield {
     pub defense_bonus: i32,
@@ -31,3 +60,12 @@ pub struct RawShield {
 pub struct RawWeapon {
     pub damage_bonus: i32,
 }
+
+#[derive(Deserialize, Debug)]
+pub struct RawWearable {
+    pub slot: EquipmentSlot,
+    #[serde(default)]
+    pub defense_bonus: i32,
+    #[serde(default)]
+    pub damage_bonus: i32,
+}
@@ -1,13 +1,26 @@
 use super::item_structs::RawRender;
+use crate::raws::faction::DEFAULT_FACTION;
 use serde::Deserialize;
 
+fn default_faction() -> String {
+    DEFAULT_FACTION.to_string()
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Mob {
     pub name: String,
+    #[serde(default = "default_faction")]
+    pub faction: String,
     pub blocks_tile: bool,
     pub vision_range: i32,
     pub render: RawRender,
     pub stats: RawStats,
+    pub loot_table: Option<String>,
+    pub equipment: Option<Vec<String>>,
+    pub inventory: Option<Vec<String>>,
+    pub regeneration: Option<i32>,
+    ///The range of the light the mob gives off
+    pub light: Option<i32>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -1,10 +1,16 @@
+mod affix_structs;
 mod item_structs;
 mod mob_structs;
+mod prop_structs;
 mod spawn_master;
 mod spawn_table_structs;
+mod trap_structs;
 
 use std::sync::Mutex;
 
+use crate::ecs::item_effects::EffectError;
+
+pub use item_structs::Unidentified;
 pub use spawn_master::{SpawnMaster, SpawnType};
 
 //In rust you are not able to use const string slices inside of macros, and because I don't want to
@@ -21,7 +27,7 @@ lazy_static::lazy_static! {
 
 rltk::embedded_resource!(RAW_SPAWNS, raw_spawns_path!());
 
-pub fn load() {
+pub fn load() -> Result<(), EffectError> {
     rltk::link_resource!(RAW_SPAWNS, raw_spawns_path!());
     let spawn_raw = rltk::embedding::EMBED
         .lock()
@@ -29,5 +35,5 @@ pub fn load() {
         .unwrap();
     let decoder: spawn_master::RawData =
         ron::de::from_bytes(spawn_raw).expect("Unable to parse RON");
-    SPAWN_RAWS.lock().unwrap().load(decoder);
+    SPAWN_RAWS.lock().unwrap().load(decoder)
 }
@@ -0,0 +1,13 @@
+use super::item_structs::RawRender;
+use serde::Deserialize;
+
+///Scenery like braziers, which can't be picked up or fought
+#[derive(Deserialize, Debug)]
+pub struct Prop {
+    pub name: String,
+    pub render: RawRender,
+    #[serde(default)]
+    pub blocks_tile: bool,
+    ///The range of the light the prop gives off
+    pub light: Option<i32>,
+}
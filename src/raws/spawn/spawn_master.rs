@@ -1,23 +1,45 @@
-use super::item_structs::RawRender;
-use crate::{components::*, constants::colors, spawning::RandomTable};
-use rltk::ColorPair;
+use super::{
+    affix_structs::{Affix, AffixKind, Rarity},
+    item_structs::{RawRender, Unidentified},
+};
+use crate::{
+    components::*,
+    constants::colors,
+    ecs::item_effects::{EffectError, EFFECTS},
+    map_builder::map::Map,
+    spawning::RandomTable,
+};
+use rltk::{ColorPair, RandomNumberGenerator};
 use serde::Deserialize;
 use specs::{
     saveload::{MarkedBuilder, SimpleMarker},
-    Builder, Entity, EntityBuilder,
+    Builder, Entity, EntityBuilder, WorldExt,
 };
 use std::collections::HashMap;
 
 #[derive(Copy, Clone)]
 pub enum SpawnType {
     AtPosition(i32, i32),
+    Carried(Entity),
+    Equipped(Entity),
 }
 
 #[derive(Deserialize, Debug)]
 pub struct RawData {
     pub mobs: Vec<super::mob_structs::Mob>,
     pub items: Vec<super::item_structs::Item>,
+    #[serde(default)]
+    pub traps: Vec<super::trap_structs::Trap>,
+    #[serde(default)]
+    pub props: Vec<super::prop_structs::Prop>,
     pub spawn_table: Vec<super::spawn_table_structs::Entry>,
+    pub loot_tables: Vec<super::spawn_table_structs::LootTable>,
+    pub bosses: Vec<super::spawn_table_structs::BossEntry>,
+    pub finale: Option<super::spawn_table_structs::Finale>,
+    #[serde(default)]
+    pub rarities: Vec<Rarity>,
+    #[serde(default)]
+    pub affixes: Vec<Affix>,
 }
 
 impl RawData {
@@ -25,7 +47,14 @@ impl RawData {
         Self {
             mobs: Vec::new(),
             items: Vec::new(),
+            traps: Vec::new(),
+            props: Vec::new(),
             spawn_table: Vec::new(),
+            loot_tables: Vec::new(),
+            bosses: Vec::new(),
+            finale: None,
+            rarities: Vec::new(),
+            affixes: Vec::new(),
         }
     }
 }
@@ -34,6 +63,9 @@ pub struct SpawnMaster {
     raw_data: RawData,
     mob_index: HashMap<String, usize>,
     item_index: HashMap<String, usize>,
+    trap_index: HashMap<String, usize>,
+    prop_index: HashMap<String, usize>,
+    loot_index: HashMap<String, usize>,
 }
 
 impl SpawnMaster {
@@ -42,10 +74,22 @@ impl SpawnMaster {
             raw_data: RawData::new(),
             mob_index: HashMap::new(),
             item_index: HashMap::new(),
+            trap_index: HashMap::new(),
+            prop_index: HashMap::new(),
+            loot_index: HashMap::new(),
         }
     }
 
-    pub fn load(&mut self, raws: RawData) {
+    pub fn load(&mut self, raws: RawData) -> Result<(), EffectError> {
+        for item in &raws.items {
+            if let Some(consumable) = &item.consumable {
+                EFFECTS.validate(&item.name, &consumable.effects)?;
+            }
+            if let Some(charged) = &item.charged {
+                EFFECTS.validate(&item.name, &charged.effects)?;
+            }
+        }
+
         self.raw_data = raws;
 
         for (i, item) in self.raw_data.mobs.iter().enumerate() {
@@ -55,6 +99,20 @@ impl SpawnMaster {
         for (i, item) in self.raw_data.items.iter().enumerate() {
             self.item_index.insert(item.name.clone(), i);
         }
+
+        for (i, trap) in self.raw_data.traps.iter().enumerate() {
+            self.trap_index.insert(trap.name.clone(), i);
+        }
+
+        for (i, prop) in self.raw_data.props.iter().enumerate() {
+            self.prop_index.insert(prop.name.clone(), i);
+        }
+
+        for (i, loot) in self.raw_data.loot_tables.iter().enumerate() {
+            self.loot_index.insert(loot.name.clone(), i);
+        }
+
+        Ok(())
     }
 
     pub fn spawn_table(&self, depth: i32) -> RandomTable {
@@ -76,6 +134,46 @@ impl SpawnMaster {
         table
     }
 
+    pub fn bosses(&self, depth: i32) -> Vec<String> {
+        self.raw_data
+            .bosses
+            .iter()
+            .filter(|boss| boss.depth == depth)
+            .map(|boss| boss.name.clone())
+            .collect()
+    }
+
+    ///Returns the name of the artifact if `depth` is the final depth of the dungeon
+    pub fn artifact(&self, depth: i32) -> Option<String> {
+        self.raw_data
+            .finale
+            .as_ref()
+            .filter(|finale| finale.depth == depth)
+            .map(|finale| finale.artifact.clone())
+    }
+
+    pub fn unidentified_items(&self) -> Vec<(String, Unidentified)> {
+        self.raw_data
+            .items
+            .iter()
+            .filter_map(|item| item.unidentified.map(|kind| (item.name.clone(), kind)))
+            .collect()
+    }
+
+    ///Rolls on the loot table with the given name, returning the name of the drop if there is one
+    pub fn roll_loot(&self, table_name: &str, rng: &mut RandomNumberGenerator) -> Option<String> {
+        let loot_table = &self.raw_data.loot_tables[*self.loot_index.get(table_name)?];
+        if rng.roll_dice(1, 100) > loot_table.drop_chance {
+            return None;
+        }
+
+        let mut table = RandomTable::new();
+        for drop in &loot_table.drops {
+            table.insert(&drop.name, drop.weight);
+        }
+        table.roll(rng)
+    }
+
     pub fn spawn_named_entity(
         &self,
         new_entity: EntityBuilder<'_>,
@@ -83,9 +181,15 @@ impl SpawnMaster {
         pos: SpawnType,
     ) -> Option<Entity> {
         if self.item_index.contains_key(key) {
-            Some(self.spawn_named_item(new_entity, self.item_index[key], pos))
+            //Effects were validated when the raws were loaded, so building them can't fail here
+            self.spawn_named_item(new_entity, self.item_index[key], pos)
+                .ok()
         } else if self.mob_index.contains_key(key) {
             Some(self.spawn_named_mob(new_entity, self.mob_index[key], pos))
+        } else if self.trap_index.contains_key(key) {
+            Some(self.spawn_named_trap(new_entity, self.trap_index[key], pos))
+        } else if self.prop_index.contains_key(key) {
+            Some(self.spawn_named_prop(new_entity, self.prop_index[key], pos))
         } else {
             None
         }
@@ -96,63 +200,164 @@ impl SpawnMaster {
         mut new_entity: EntityBuilder<'_>,
         index: usize,
         pos: SpawnType,
-    ) -> Entity {
+    ) -> Result<Entity, EffectError> {
         let item_template = &self.raw_data.items[index];
+        let slot = Self::equipment_slot(item_template);
+
+        //Equipment may roll a rarity with affixes, which change its name, color and bonuses
+        let (rarity, affixes) = match slot {
+            Some(slot) if !item_template.artifact => {
+                let depth = new_entity
+                    .world
+                    .try_fetch::<Map>()
+                    .map_or(1, |map| map.depth);
+                self.roll_affixes(slot, depth, &mut RandomNumberGenerator::new())
+            }
+            _ => (None, Vec::new()),
+        };
+        let render = RawRender {
+            color: rarity
+                .and_then(|rarity| rarity.color)
+                .unwrap_or(item_template.render.color),
+            ..item_template.render
+        };
 
         //Assign required components
         new_entity = new_entity
             .with(Item {})
             .with(Name {
-                name: item_template.name.clone(),
+                name: Self::affixed_name(&item_template.name, &affixes),
             })
             .marked::<SimpleMarker<SerializeMe>>();
-        new_entity = Self::assign_render(new_entity, &item_template.render);
-        new_entity = Self::assign_position(new_entity, &pos);
+        new_entity = Self::assign_render(new_entity, &render);
+        new_entity = Self::assign_position(new_entity, &pos, slot);
 
         //Assign optional components
         if let Some(consumable) = &item_template.consumable {
-            new_entity = new_entity.with(Consumable {});
-            //Assign effect components
-            for effect in &consumable.effects {
-                new_entity = match effect.0.as_str() {
-                    "provides_healing" => new_entity.with(ProvidesHealing {
-                        heal_amount: effect.1.parse().unwrap(),
-                    }),
-                    "range" => new_entity.with(Range {
-                        range: effect.1.parse().unwrap(),
-                    }),
-                    "damage" => new_entity.with(InflictsDamage {
-                        damage: effect.1.parse().unwrap(),
-                    }),
-                    "area_of_effect" => new_entity.with(AreaOfEffect {
-                        radius: effect.1.parse().unwrap(),
-                    }),
-                    name => panic!("Consumable effect \"{}\" not implemented", name),
-                }
-            }
+            new_entity = new_entity
+                .with(Consumable {})
+                .with(Stackable { quantity: 1 });
+            new_entity =
+                Self::assign_effects(new_entity, &item_template.name, &consumable.effects)?;
         }
 
-        if let Some(weapon) = &item_template.weapon {
-            new_entity = new_entity
-                .with(MeleeDamageBonus {
-                    bonus: weapon.damage_bonus,
-                })
-                .with(Equipment {
-                    slot: EquipmentSlot::PrimaryHand,
-                });
+        if let Some(charged) = &item_template.charged {
+            new_entity = new_entity.with(Charges {
+                remaining: charged.charges,
+                crumbles: charged.crumbles,
+            });
+            new_entity = Self::assign_effects(new_entity, &item_template.name, &charged.effects)?;
         }
 
-        if let Some(shield) = &item_template.shield {
-            new_entity = new_entity
-                .with(DefenseBonus {
-                    bonus: shield.defense_bonus,
-                })
-                .with(Equipment {
-                    slot: EquipmentSlot::OffHand,
-                })
+        if item_template.artifact {
+            new_entity = new_entity.with(Artifact {});
         }
 
-        new_entity.build()
+        let weight =
+            item_template.weight + affixes.iter().map(|affix| affix.item_weight).sum::<i32>();
+        if weight > 0 {
+            new_entity = new_entity.with(Weight { weight });
+        }
+
+        if item_template.cursed {
+            new_entity = new_entity.with(Cursed { revealed: false });
+        }
+
+        if let Some(range) = item_template.light {
+            new_entity = new_entity.with(LightSource { range });
+        }
+
+        if let Some(slot) = slot {
+            let (base_damage, base_defense) = Self::base_bonuses(item_template);
+            let damage = base_damage + affixes.iter().map(|affix| affix.damage_bonus).sum::<i32>();
+            let defense =
+                base_defense + affixes.iter().map(|affix| affix.defense_bonus).sum::<i32>();
+
+            new_entity = new_entity.with(Equipment { slot });
+            if damage != 0 {
+                new_entity = new_entity.with(MeleeDamageBonus { bonus: damage });
+            }
+            if defense != 0 {
+                new_entity = new_entity.with(DefenseBonus { bonus: defense });
+            }
+        }
+
+        Ok(new_entity.build())
+    }
+
+    ///Rolls the rarity of an item for the depth, and an affix for every one the rarity grants
+    fn roll_affixes(
+        &self,
+        slot: EquipmentSlot,
+        depth: i32,
+        rng: &mut RandomNumberGenerator,
+    ) -> (Option<&Rarity>, Vec<&Affix>) {
+        let mut rarities = RandomTable::new();
+        for rarity in self
+            .raw_data
+            .rarities
+            .iter()
+            .filter(|rarity| rarity.min_depth <= depth)
+        {
+            rarities.insert(
+                &rarity.name,
+                rarity.weight + rarity.weight_per_depth * depth,
+            );
+        }
+        let Some(rarity) = rarities
+            .roll(rng)
+            .and_then(|name| self.raw_data.rarities.iter().find(|r| r.name == name))
+        else {
+            return (None, Vec::new());
+        };
+
+        let kinds = match rarity.affixes {
+            0 => vec![],
+            1 if rng.roll_dice(1, 2) == 1 => vec![AffixKind::Prefix],
+            1 => vec![AffixKind::Suffix],
+            _ => vec![AffixKind::Prefix, AffixKind::Suffix],
+        };
+        let affixes = kinds
+            .into_iter()
+            .filter_map(|kind| {
+                let mut table = RandomTable::new();
+                for affix in self.raw_data.affixes.iter().filter(|affix| {
+                    affix.kind == kind
+                        && affix.min_depth <= depth
+                        && (affix.slots.is_empty() || affix.slots.contains(&slot))
+                }) {
+                    table.insert(&affix.name, affix.weight);
+                }
+                table
+                    .roll(rng)
+                    .and_then(|name| self.raw_data.affixes.iter().find(|a| a.name == name))
+            })
+            .collect();
+
+        (Some(rarity), affixes)
+    }
+
+    fn affixed_name(base: &str, affixes: &[&Affix]) -> String {
+        affixes
+            .iter()
+            .fold(base.to_string(), |name, affix| match affix.kind {
+                AffixKind::Prefix => format!("{} {}", affix.name, name),
+                AffixKind::Suffix => format!("{} {}", name, affix.name),
+            })
+    }
+
+    ///The damage and defense bonuses of an item before any affixes
+    fn base_bonuses(item: &super::item_structs::Item) -> (i32, i32) {
+        let weapon = item.weapon.as_ref().map_or(0, |weapon| weapon.damage_bonus);
+        let shield = item
+            .shield
+            .as_ref()
+            .map_or(0, |shield| shield.defense_bonus);
+        let (wearable_damage, wearable_defense) =
+            item.wearable.as_ref().map_or((0, 0), |wearable| {
+                (wearable.damage_bonus, wearable.defense_bonus)
+            });
+        (weapon + wearable_damage, shield + wearable_defense)
     }
 
     fn spawn_named_mob(
@@ -169,6 +374,9 @@ impl SpawnMaster {
             .with(Name {
                 name: mob_template.name.clone(),
             })
+            .with(Faction {
+                name: mob_template.faction.clone(),
+            })
             .with(CombatStats {
                 max_hp: mob_template.stats.max_hp,
                 hp: mob_template.stats.max_hp,
@@ -182,20 +390,153 @@ impl SpawnMaster {
             })
             .marked::<SimpleMarker<SerializeMe>>();
         new_entity = Self::assign_render(new_entity, &mob_template.render);
-        new_entity = Self::assign_position(new_entity, &pos);
+        new_entity = Self::assign_position(new_entity, &pos, None);
         if mob_template.blocks_tile {
             new_entity = new_entity.with(BlocksTile {})
         }
+        if let Some(loot_table) = &mob_template.loot_table {
+            new_entity = new_entity.with(LootTable {
+                table: loot_table.clone(),
+            });
+        }
+
+        if let Some(amount) = mob_template.regeneration {
+            new_entity = new_entity.with(Regeneration { amount });
+        }
+
+        if let Some(range) = mob_template.light {
+            new_entity = new_entity.with(LightSource { range });
+        }
+
+        //The world is kept to be able to spawn the mobs starting items after it is built
+        let world = new_entity.world;
+        let mob = new_entity.build();
+
+        for item in mob_template.equipment.iter().flatten() {
+            self.spawn_named_entity(
+                world.create_entity_unchecked(),
+                item,
+                SpawnType::Equipped(mob),
+            );
+        }
+
+        for item in mob_template.inventory.iter().flatten() {
+            self.spawn_named_entity(
+                world.create_entity_unchecked(),
+                item,
+                SpawnType::Carried(mob),
+            );
+        }
+
+        mob
+    }
+
+    fn spawn_named_trap(
+        &self,
+        mut new_entity: EntityBuilder<'_>,
+        index: usize,
+        pos: SpawnType,
+    ) -> Entity {
+        let trap_template = &self.raw_data.traps[index];
+
+        //Assign required components
+        new_entity = new_entity
+            .with(Trap {
+                spot_chance: trap_template.spot_chance,
+                single_use: trap_template.single_use,
+            })
+            .with(Hidden {})
+            .with(Name {
+                name: trap_template.name.clone(),
+            })
+            .marked::<SimpleMarker<SerializeMe>>();
+        new_entity = Self::assign_render(new_entity, &trap_template.render);
+        new_entity = Self::assign_position(new_entity, &pos, None);
+
+        //Assign optional components
+        if let Some(damage) = trap_template.damage {
+            new_entity = new_entity.with(InflictsDamage { damage });
+        }
+        if let Some(turns) = trap_template.immobilize {
+            new_entity = new_entity.with(Immobilizes { turns });
+        }
+        if trap_template.teleport {
+            new_entity = new_entity.with(Teleports {});
+        }
+
+        new_entity.build()
+    }
+
+    fn spawn_named_prop(
+        &self,
+        mut new_entity: EntityBuilder<'_>,
+        index: usize,
+        pos: SpawnType,
+    ) -> Entity {
+        let prop_template = &self.raw_data.props[index];
+
+        //Assign required components
+        new_entity = new_entity
+            .with(Name {
+                name: prop_template.name.clone(),
+            })
+            .marked::<SimpleMarker<SerializeMe>>();
+        new_entity = Self::assign_render(new_entity, &prop_template.render);
+        new_entity = Self::assign_position(new_entity, &pos, None);
+
+        //Assign optional components
+        if prop_template.blocks_tile {
+            new_entity = new_entity.with(BlocksTile {});
+        }
+        if let Some(range) = prop_template.light {
+            new_entity = new_entity.with(LightSource { range });
+        }
 
         new_entity.build()
     }
 
-    fn assign_position<'a>(new_entity: EntityBuilder<'a>, pos: &SpawnType) -> EntityBuilder<'a> {
-        match pos {
-            SpawnType::AtPosition(x, y) => new_entity.with(Position { x: *x, y: *y }),
+    ///Items that can't be equipped are placed into the owners backpack instead
+    fn assign_position<'a>(
+        new_entity: EntityBuilder<'a>,
+        pos: &SpawnType,
+        slot: Option<EquipmentSlot>,
+    ) -> EntityBuilder<'a> {
+        match (pos, slot) {
+            (SpawnType::AtPosition(x, y), _) => new_entity.with(Position { x: *x, y: *y }),
+            (SpawnType::Equipped(owner), Some(slot)) => new_entity.with(Equipped {
+                owner: *owner,
+                slot,
+            }),
+            (SpawnType::Carried(owner), _) | (SpawnType::Equipped(owner), None) => {
+                new_entity.with(InBackpack { owner: *owner })
+            }
         }
     }
 
+    const fn equipment_slot(item: &super::item_structs::Item) -> Option<EquipmentSlot> {
+        if let Some(wearable) = &item.wearable {
+            Some(wearable.slot)
+        } else if item.weapon.is_some() {
+            Some(EquipmentSlot::PrimaryHand)
+        } else if item.shield.is_some() {
+            Some(EquipmentSlot::OffHand)
+        } else {
+            None
+        }
+    }
+
+    ///Assigns effect components, which were validated when the raws were loaded
+    fn assign_effects<'a>(
+        mut new_entity: EntityBuilder<'a>,
+        item_name: &str,
+        effects: &HashMap<String, String>,
+    ) -> Result<EntityBuilder<'a>, EffectError> {
+        for (field, value) in effects {
+            new_entity = EFFECTS.build(new_entity, item_name, field, value)?;
+        }
+        Ok(new_entity)
+    }
+
     fn assign_render<'a>(new_entity: EntityBuilder<'a>, render: &RawRender) -> EntityBuilder<'a> {
         let colors = ColorPair::new(render.color, colors::BACKGROUND);
         new_entity.with(Render {
@@ -205,3 +546,56 @@ impl SpawnMaster {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raws::spawn::SPAWN_RAWS;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn affixes_roll_only_where_the_raws_allow_them() {
+        crate::raws::spawn::load().unwrap();
+        let spawns = SPAWN_RAWS.lock().unwrap();
+        let mut rng = RandomNumberGenerator::seeded(7);
+
+        for depth in 1..=8 {
+            for slot in EquipmentSlot::iter() {
+                for _ in 0..20 {
+                    let (rarity, affixes) = spawns.roll_affixes(slot, depth, &mut rng);
+                    let rarity = rarity.unwrap();
+                    assert!(rarity.min_depth <= depth);
+                    assert!(affixes.len() as i32 <= rarity.affixes);
+                    for affix in &affixes {
+                        assert!(affix.min_depth <= depth);
+                        assert!(affix.slots.is_empty() || affix.slots.contains(&slot));
+                    }
+                    let prefixes = affixes
+                        .iter()
+                        .filter(|affix| affix.kind == AffixKind::Prefix)
+                        .count();
+                    assert!(prefixes <= 1 && affixes.len() - prefixes <= 1);
+                }
+            }
+        }
+        drop(spawns);
+    }
+
+    #[test]
+    fn prefixes_go_before_the_name_and_suffixes_after() {
+        crate::raws::spawn::load().unwrap();
+        let spawns = SPAWN_RAWS.lock().unwrap();
+        let affix = |name: &str| {
+            spawns
+                .raw_data
+                .affixes
+                .iter()
+                .find(|affix| affix.name == name)
+                .unwrap()
+        };
+        assert_eq!(
+            SpawnMaster::affixed_name("Longsword", &[affix("of Slaying"), affix("Sharp")]),
+            "Sharp Longsword of Slaying"
+        );
+    }
+}
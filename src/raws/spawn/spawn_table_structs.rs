@@ -8,3 +8,28 @@ pub struct Entry {
     pub max_depth: i32,
     pub scales_to_depth: bool,
 }
+
+#[derive(Deserialize, Debug)]
+pub struct LootTable {
+    pub name: String,
+    pub drop_chance: i32,
+    pub drops: Vec<LootDrop>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LootDrop {
+    pub name: String,
+    pub weight: i32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BossEntry {
+    pub name: String,
+    pub depth: i32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Finale {
+    pub depth: i32,
+    pub artifact: String,
+}
@@ -0,0 +1,18 @@
+use super::item_structs::RawRender;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct Trap {
+    pub name: String,
+    pub render: RawRender,
+    ///The chance in percent that the player spots the trap each time it comes into view
+    pub spot_chance: i32,
+    ///Single use traps are destroyed once sprung
+    #[serde(default)]
+    pub single_use: bool,
+    pub damage: Option<i32>,
+    ///How many turns the victim is held in place
+    pub immobilize: Option<i32>,
+    #[serde(default)]
+    pub teleport: bool,
+}
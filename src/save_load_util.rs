@@ -1,4 +1,4 @@
-use crate::{components::*, map_builder::map::Map};
+use crate::{components::*, ecs::identification::Identities, map_builder::map::Map};
 use specs::{
     error::NoError,
     prelude::*,
@@ -42,9 +42,13 @@ macro_rules! deserialize_individually {
 
 pub fn save_game(ecs: &mut World) {
     let map_copy = ecs.get_mut::<Map>().unwrap().clone();
+    let identities = (*ecs.fetch::<Identities>()).clone();
     let save_helper = ecs
         .create_entity()
-        .with(SerializationHelper { map: map_copy })
+        .with(SerializationHelper {
+            map: map_copy,
+            identities,
+        })
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
     {
@@ -60,32 +64,55 @@ pub fn save_game(ecs: &mut World) {
             serializer,
             data,
             AreaOfEffect,
+            Artifact,
             BlocksTile,
             CombatStats,
+            Charges,
             Consumable,
+            Corpse,
+            Cursed,
             DefenseBonus,
+            EntityMoved,
             Equipment,
             Equipped,
+            Faction,
+            Hidden,
+            HungerClock,
+            Identifies,
+            Immobilized,
+            Immobilizes,
             InBackpack,
             InflictsDamage,
             Item,
+            LootTable,
+            MagicMapper,
             MeleeDamageBonus,
             Monster,
             Name,
+            Overburdened,
             ParticleLifetime,
             Player,
             Position,
+            ProvidesFood,
             ProvidesHealing,
             Range,
+            Regeneration,
+            LightSource,
+            RemovesCurse,
             Render,
             SerializationHelper,
+            Stackable,
             SufferDamage,
+            Teleports,
+            TownPortal,
+            Trap,
             FieldOfView,
             WantsToDropItem,
             WantsToMelee,
             WantsToPickupItem,
             WantsToRemoveItem,
             WantsToUseItem,
+            Weight,
         );
     }
 
@@ -118,32 +145,55 @@ pub fn load_game(ecs: &mut World) {
             de,
             d,
             AreaOfEffect,
+            Artifact,
             BlocksTile,
             CombatStats,
+            Charges,
             Consumable,
+            Corpse,
+            Cursed,
             DefenseBonus,
+            EntityMoved,
             Equipment,
             Equipped,
+            Faction,
+            Hidden,
+            HungerClock,
+            Identifies,
+            Immobilized,
+            Immobilizes,
             InBackpack,
             InflictsDamage,
             Item,
+            LootTable,
+            MagicMapper,
             MeleeDamageBonus,
             Monster,
             Name,
+            Overburdened,
             ParticleLifetime,
             Player,
             Position,
+            ProvidesFood,
             ProvidesHealing,
             Range,
+            Regeneration,
+            LightSource,
+            RemovesCurse,
             Render,
             SerializationHelper,
+            Stackable,
             SufferDamage,
+            Teleports,
+            TownPortal,
+            Trap,
             FieldOfView,
             WantsToDropItem,
             WantsToMelee,
             WantsToPickupItem,
             WantsToRemoveItem,
             WantsToUseItem,
+            Weight,
         );
     }
 
@@ -156,8 +206,11 @@ pub fn load_game(ecs: &mut World) {
         for (e, h) in (&entities, &helper).join() {
             let mut world_map = ecs.write_resource::<Map>();
             *world_map = h.map.clone();
-            world_map.tile_content =
-                vec![Vec::new(); (world_map.width * world_map.height) as usize];
+            let tile_count = (world_map.width * world_map.height) as usize;
+            world_map.tile_content = vec![Vec::new(); tile_count];
+            world_map.light = vec![0.0; tile_count];
+            world_map.memories.resize(tile_count, None);
+            *ecs.write_resource::<Identities>() = h.identities.clone();
             delete_me = Some(e);
         }
         for (e, _, pos) in (&entities, &player, &position).join() {
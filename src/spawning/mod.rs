@@ -3,5 +3,6 @@ mod spawner;
 
 pub use random_table::RandomTable;
 pub use spawner::populate_room;
+pub use spawner::spawn_depth_specials;
 pub use spawner::spawn_player;
 pub use spawner::spawn_region;
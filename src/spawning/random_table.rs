@@ -24,17 +24,18 @@ impl RandomTable {
         if self.total_weight == 0 {
             return None;
         }
-        let mut roll = rng.roll_dice(1, self.total_weight) - 1;
-        let mut index = 0;
+        self.pick(rng.roll_dice(1, self.total_weight) - 1)
+    }
 
-        while roll > 0 {
-            if roll < self.entries[index].weight {
-                return Some(self.entries[index].name.clone());
+    ///The entry a roll from `0` to `total_weight - 1` lands on. Each entry covers as many rolls as
+    ///its weight, so a roll of `0` is the first entry rather than nothing at all.
+    fn pick(&self, mut roll: i32) -> Option<String> {
+        for entry in &self.entries {
+            if roll < entry.weight {
+                return Some(entry.name.clone());
             }
-            roll -= self.entries[index].weight;
-            index += 1;
+            roll -= entry.weight;
         }
-
         None
     }
 }
@@ -52,3 +53,35 @@ impl RandomEntry {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_roll_of_zero_picks_the_first_entry() {
+        let mut table = RandomTable::new();
+        table.insert(&"Goblin", 1);
+        table.insert(&"Orc", 1);
+        assert_eq!(table.pick(0), Some("Goblin".to_string()));
+    }
+
+    #[test]
+    fn entries_cover_as_many_rolls_as_their_weight() {
+        let mut table = RandomTable::new();
+        table.insert(&"Goblin", 3);
+        table.insert(&"Orc", 0);
+        table.insert(&"Troll", 1);
+        let picks = (0..table.total_weight)
+            .map(|roll| table.pick(roll).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(picks, ["Goblin", "Goblin", "Goblin", "Troll"]);
+        assert_eq!(table.pick(table.total_weight), None);
+    }
+
+    #[test]
+    fn empty_tables_roll_nothing() {
+        let mut rng = RandomNumberGenerator::seeded(1);
+        assert_eq!(RandomTable::new().roll(&mut rng), None);
+    }
+}
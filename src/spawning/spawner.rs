@@ -1,7 +1,13 @@
 use super::random_table::RandomTable;
 use crate::{
     constants::colors,
-    ecs::components::{CombatStats, FieldOfView, Name, Player, Position, Render, SerializeMe},
+    ecs::{
+        components::{
+            CombatStats, Faction, FieldOfView, HungerClock, HungerState, Name, Player, Position,
+            Render, SerializeMe,
+        },
+        WELL_FED_DURATION,
+    },
     map_builder::{
         map::{Map, TileType},
         rect::Rect,
@@ -13,7 +19,7 @@ use specs::{
     prelude::*,
     saveload::{MarkedBuilder, SimpleMarker},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 const MAX_MONSTERS: i32 = 4;
 
@@ -64,7 +70,8 @@ pub fn spawn_region(ecs: &mut World, area: &[(i32, i32)], map_depth: i32) {
 }
 
 pub fn spawn_player(ecs: &mut World, x: i32, y: i32) -> Entity {
-    ecs.create_entity()
+    let player = ecs
+        .create_entity()
         .with(Position { x, y })
         .with(Player {})
         .with(Render {
@@ -80,14 +87,100 @@ pub fn spawn_player(ecs: &mut World, x: i32, y: i32) -> Entity {
         .with(Name {
             name: "Player".to_string(),
         })
+        .with(Faction {
+            name: "Player".to_string(),
+        })
         .with(CombatStats {
             max_hp: 30,
             hp: 30,
             defense: 2,
             power: 5,
         })
+        .with(HungerClock {
+            state: HungerState::WellFed,
+            duration: WELL_FED_DURATION,
+        })
         .marked::<SimpleMarker<SerializeMe>>()
-        .build()
+        .build();
+
+    //The deeper levels are dark, so the player sets out with a torch in hand
+    SPAWN_RAWS.lock().unwrap().spawn_named_entity(
+        ecs.create_entity(),
+        "Torch",
+        SpawnType::Equipped(player),
+    );
+    player
+}
+
+///Places the artifact of the final depth where the stairs would have been, and the bosses of the
+///current depth on free floor tiles around it.
+pub fn spawn_depth_specials(ecs: &mut World) {
+    let (depth, stairs) = {
+        let map = ecs.fetch::<Map>();
+        let stairs_idx = map
+            .tiles
+            .iter()
+            .position(|tile| *tile == TileType::StairsDown);
+        let stairs = stairs_idx.map(|idx| (idx as i32 % map.width, idx as i32 / map.width));
+        (map.depth, stairs)
+    };
+
+    let Some((x, y)) = stairs else {
+        return;
+    };
+
+    let spawn_raws = SPAWN_RAWS.lock().unwrap();
+    if let Some(artifact) = spawn_raws.artifact(depth) {
+        let mut map = ecs.fetch_mut::<Map>();
+        let idx = map.xy_idx(x, y);
+        map.tiles[idx] = TileType::Floor;
+        std::mem::drop(map);
+        spawn_raws.spawn_named_entity(ecs.create_entity(), &artifact, SpawnType::AtPosition(x, y));
+    }
+
+    let bosses = spawn_raws.bosses(depth);
+    let tiles = free_tiles_near(ecs, (x, y), bosses.len());
+    for (boss, (x, y)) in bosses.iter().zip(tiles) {
+        spawn_raws.spawn_named_entity(ecs.create_entity(), boss, SpawnType::AtPosition(x, y));
+    }
+}
+
+///Up to `count` floor tiles with nothing on them, nearest first, found by walking out from `start`
+///so that none of them end up behind a wall
+fn free_tiles_near(ecs: &World, start: (i32, i32), count: usize) -> Vec<(i32, i32)> {
+    let map = ecs.fetch::<Map>();
+    let positions = ecs.read_storage::<Position>();
+    let mut occupied = vec![false; map.tiles.len()];
+    for pos in positions.join() {
+        occupied[map.xy_idx(pos.x, pos.y)] = true;
+    }
+
+    let mut visited = vec![false; map.tiles.len()];
+
+    let mut free = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    visited[map.xy_idx(start.0, start.1)] = true;
+    while let Some((x, y)) = queue.pop_front() {
+        if free.len() == count {
+            break;
+        }
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || nx >= map.width || ny < 0 || ny >= map.height {
+                continue;
+            }
+            let idx = map.xy_idx(nx, ny);
+            if visited[idx] || matches!(map.tiles[idx], TileType::Wall | TileType::SecretDoor) {
+                continue;
+            }
+            visited[idx] = true;
+            if map.tiles[idx] == TileType::Floor && !occupied[idx] && free.len() < count {
+                free.push((nx, ny));
+            }
+            queue.push_back((nx, ny));
+        }
+    }
+    free
 }
 
 fn create_room_table(map_depth: i32) -> RandomTable {
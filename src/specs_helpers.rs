@@ -1,12 +1,14 @@
 use super::{
-    ecs::{components::*, ParticleBuilder},
+    ecs::{components::*, identification::Identities, ParticleBuilder},
     game_log::GameLog,
+    gui::{context_menu::RightClick, targeting::LastTarget},
     rex_assets::RexAssets,
     state::{MainOption, Menu, State},
+    travel::Travel,
 };
 use specs::{
     prelude::*,
-    saveload::{SimpleMarker, SimpleMarkerAllocator},
+    saveload::{MarkedBuilder, SimpleMarker, SimpleMarkerAllocator},
 };
 
 ///Given a specs::World, and a list of components, it registers all components in the world
@@ -27,38 +29,142 @@ macro_rules! insert_all {
     };
 }
 
+///Given a `specs::World`, two entities, and a list of components, it copies every listed component
+///the source entity has onto the target entity
+macro_rules! clone_all {
+    ($ecs:expr, $source:expr, $target:expr, $($component:ty),* $(,)*) => {
+        {
+            $(
+            let component = $ecs.read_storage::<$component>().get($source).cloned();
+            if let Some(component) = component {
+                $ecs.write_storage::<$component>()
+                    .insert($target, component)
+                    .expect("Unable to copy component");
+            }
+            )*
+        }
+    };
+}
+
 pub fn register_all_components(world: &mut specs::World) {
     register_all!(
         world,
         AreaOfEffect,
+        Artifact,
         BlocksTile,
         CombatStats,
+        Charges,
         Consumable,
+        Corpse,
+        Cursed,
         DefenseBonus,
+        EntityMoved,
         Equipment,
         Equipped,
+        Faction,
+        Hidden,
+        HungerClock,
+        Identifies,
+        Immobilized,
+        Immobilizes,
         InBackpack,
         InflictsDamage,
         Item,
+        LootTable,
+        MagicMapper,
         MeleeDamageBonus,
         Monster,
         Name,
+        Overburdened,
         ParticleLifetime,
         Player,
         Position,
+        ProvidesFood,
         ProvidesHealing,
         Range,
+        Regeneration,
+        LightSource,
+        RemovesCurse,
         Render,
         SerializationHelper,
         SimpleMarker<SerializeMe>,
+        Stackable,
+        SufferDamage,
+        Teleports,
+        TownPortal,
+        Trap,
+        FieldOfView,
+        WantsToDropItem,
+        WantsToMelee,
+        WantsToPickupItem,
+        WantsToRemoveItem,
+        WantsToUseItem,
+        Weight,
+    );
+}
+
+///Creates a new saved entity with a copy of every component of `source`. Keep the list in step with
+///`register_all_components`, leaving out the save marker and helper.
+pub fn clone_entity(world: &mut specs::World, source: Entity) -> Entity {
+    let target = world
+        .create_entity()
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+    clone_all!(
+        world,
+        source,
+        target,
+        AreaOfEffect,
+        Artifact,
+        BlocksTile,
+        CombatStats,
+        Charges,
+        Consumable,
+        Corpse,
+        Cursed,
+        DefenseBonus,
+        EntityMoved,
+        Equipment,
+        Equipped,
+        Faction,
+        Hidden,
+        HungerClock,
+        Identifies,
+        Immobilized,
+        Immobilizes,
+        InBackpack,
+        InflictsDamage,
+        Item,
+        LootTable,
+        MagicMapper,
+        MeleeDamageBonus,
+        Monster,
+        Name,
+        Overburdened,
+        ParticleLifetime,
+        Player,
+        Position,
+        ProvidesFood,
+        ProvidesHealing,
+        Range,
+        Regeneration,
+        LightSource,
+        RemovesCurse,
+        Render,
+        Stackable,
         SufferDamage,
+        Teleports,
+        TownPortal,
+        Trap,
         FieldOfView,
         WantsToDropItem,
         WantsToMelee,
         WantsToPickupItem,
         WantsToRemoveItem,
         WantsToUseItem,
+        Weight,
     );
+    target
 }
 
 pub fn insert_all_resources(world: &mut specs::World) {
@@ -71,6 +177,10 @@ pub fn insert_all_resources(world: &mut specs::World) {
         RexAssets::load(),
         ParticleBuilder::new(),
         GameLog::new(),
+        Identities::new_run(),
+        LastTarget::default(),
+        Travel::default(),
+        RightClick::default(),
     );
 
     //Unable to include this statement in the above batch due to the borrow checker
@@ -17,8 +17,17 @@ pub enum Gameplay {
     PlayerTurn,
     PreRun,
     SaveGame,
-    Inventory(gui::inventory::InvMode),
-    ShowTargeting(i32, specs::Entity),
+    TownPortal,
+    Travel,
+    Victory,
+    ///The mode of the inventory, and the page of it being shown
+    Inventory(gui::inventory::InvMode, usize),
+    DropAmount(specs::Entity, i32),
+    Equipment(Option<crate::ecs::EquipmentSlot>),
+    ShowTargeting(i32, specs::Entity, rltk::Point),
+    Look(rltk::Point),
+    ContextMenu(rltk::Point),
+    RevealMap(i32),
 }
 
 #[derive(PartialEq, Copy, Clone, Debug)]
@@ -28,7 +37,10 @@ pub enum Menu {
     Audio(AudioOption),
     Visual(VisualOption),
     Keybinding(KeyBindingOption),
-    KeySelect(KeyBindingOption),
+    KeySelect(
+        KeyBindingOption,
+        Option<gui::settings::keybindings::Warning>,
+    ),
 }
 
 //Menu Options
@@ -81,7 +93,7 @@ pub enum VisualOption {
     Back,
 }
 
-#[derive(PartialEq, Copy, Clone, Debug, AsRefStr, EnumCycle, EnumIter)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, AsRefStr, EnumCycle, EnumIter)]
 pub enum KeyBindingOption {
     Right,
     Left,
@@ -97,6 +109,7 @@ pub enum KeyBindingOption {
     DownLeft,
     Descend,
     Inventory,
+    Equipment,
     #[strum(serialize = "Grab Item")]
     GrabItem,
     #[strum(serialize = "Drop Item")]
@@ -107,7 +120,22 @@ pub enum KeyBindingOption {
     GoBack,
     #[strum(serialize = "Wait a Turn")]
     WaitTurn,
+    #[strum(serialize = "Rest Until Healed")]
+    Rest,
+    #[strum(serialize = "Disarm Trap")]
+    DisarmTrap,
+    Search,
+    #[strum(serialize = "Look Around")]
+    Look,
+    #[strum(serialize = "Next Target")]
+    CycleTarget,
+    #[strum(serialize = "Auto Explore")]
+    AutoExplore,
+    #[strum(serialize = "Travel to Stairs")]
+    TravelToStairs,
     Select,
+    #[strum(serialize = "Reset to Defaults")]
+    ResetDefaults,
     #[skip]
     Back,
 }
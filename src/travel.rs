@@ -0,0 +1,287 @@
+use crate::{
+    ecs::{
+        identification::Identities, CombatStats, FieldOfView, Hidden, Item, Monster, Name, Position,
+    },
+    game_log::GameLog,
+    gui::targeting,
+    map_builder::map::{Map, TileStatus, TileType},
+    player,
+    state::Gameplay,
+};
+use rltk::{DijkstraMap, Point, Rltk};
+use specs::{Entity, Join, World, WorldExt};
+
+//Stop counting steps past this distance from the destination
+const MAX_STEPS: f32 = 2000.0;
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Destination {
+    ///Follows the shortest path to a target
+    Path(PathTarget),
+    ///Keeps going in one direction until something interesting comes up
+    Run(i32, i32),
+    ///Waits in place until the player is fully healed
+    Rest,
+}
+
+///Tiles a path can be found towards
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum PathTarget {
+    ///The nearest tile the player hasn't seen yet
+    Unexplored,
+    Stairs,
+    Tile(Point),
+}
+
+///Where the player is travelling to, one step per turn, until something interrupts them
+#[derive(Default)]
+pub struct Travel {
+    destination: Option<Destination>,
+    ///Items the player already knew about, which won't interrupt them again
+    known_items: Vec<Entity>,
+    ///How many ways there were to go from the last tile the player ran to
+    openings: Option<usize>,
+}
+
+///Sets the player off towards `destination`
+pub fn start(ecs: &World, destination: Destination) -> Gameplay {
+    let known_items = visible_items(ecs);
+    *ecs.fetch_mut::<Travel>() = Travel {
+        destination: Some(destination),
+        known_items,
+        openings: None,
+    };
+    Gameplay::Travel
+}
+
+///Where the game goes once the monsters had their turn: on with the journey, or back to the player
+pub fn after_turn(ecs: &World) -> Gameplay {
+    if ecs.fetch::<Travel>().destination.is_some() {
+        Gameplay::Travel
+    } else {
+        Gameplay::AwaitingInput
+    }
+}
+
+///Takes the next step towards the destination, unless the player pressed a key, saw something, or
+///there is nowhere left to go
+pub fn step(ecs: &mut World, ctx: &Rltk) -> Gameplay {
+    let Some(destination) = ecs.fetch::<Travel>().destination else {
+        return Gameplay::AwaitingInput;
+    };
+    if ctx.key.is_some() || is_interrupted(ecs) {
+        return stop(ecs);
+    }
+
+    match destination {
+        Destination::Rest => rest(ecs),
+        Destination::Run(delta_x, delta_y) => run(ecs, delta_x, delta_y),
+        Destination::Path(target) => {
+            let Some((delta_x, delta_y)) = next_step(ecs, target) else {
+                return stop(ecs);
+            };
+            take_step(ecs, delta_x, delta_y);
+            Gameplay::PlayerTurn
+        }
+    }
+}
+
+///Moves the player, returning whether they actually went anywhere
+fn take_step(ecs: &mut World, delta_x: i32, delta_y: i32) -> bool {
+    let before = *ecs.fetch::<Point>();
+    player::try_move(delta_x, delta_y, ecs);
+
+    //Being held in place or staggering would keep the player trying forever
+    let has_moved = *ecs.fetch::<Point>() != before;
+    if !has_moved {
+        ecs.fetch_mut::<Travel>().destination = None;
+    }
+    has_moved
+}
+
+///Runs a step while the way ahead is open, stopping on doors, stairs, items and wherever the
+///surroundings change, such as where a corridor branches off or opens into a room
+fn run(ecs: &mut World, delta_x: i32, delta_y: i32) -> Gameplay {
+    let player_pos = *ecs.fetch::<Point>();
+    let ahead = Point::new(player_pos.x + delta_x, player_pos.y + delta_y);
+    if !is_open(&ecs.fetch::<Map>(), ahead) {
+        return stop(ecs);
+    }
+    if !take_step(ecs, delta_x, delta_y) {
+        return Gameplay::PlayerTurn;
+    }
+
+    let player_pos = *ecs.fetch::<Point>();
+    let openings = {
+        let map = ecs.fetch::<Map>();
+        [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .iter()
+            .filter(|(x, y)| is_open(&map, Point::new(player_pos.x + x, player_pos.y + y)))
+            .count()
+    };
+    let is_interesting = is_interesting(ecs, player_pos);
+    let mut travel = ecs.fetch_mut::<Travel>();
+    if is_interesting || travel.openings.is_some_and(|last| last != openings) {
+        travel.destination = None;
+    }
+    travel.openings = Some(openings);
+    Gameplay::PlayerTurn
+}
+
+fn is_open(map: &Map, tile: Point) -> bool {
+    tile.x >= 0
+        && tile.x < map.width
+        && tile.y >= 0
+        && tile.y < map.height
+        && !matches!(
+            map.tiles[map.xy_idx(tile.x, tile.y)],
+            TileType::Wall | TileType::SecretDoor
+        )
+}
+
+///Doors, stairs and items are worth stopping on
+fn is_interesting(ecs: &World, tile: Point) -> bool {
+    let map = ecs.fetch::<Map>();
+    let items = ecs.read_storage::<Item>();
+    let positions = ecs.read_storage::<Position>();
+    let hidden = ecs.read_storage::<Hidden>();
+
+    matches!(
+        map.tiles[map.xy_idx(tile.x, tile.y)],
+        TileType::Door | TileType::StairsDown
+    ) || (&items, &positions, !&hidden)
+        .join()
+        .any(|(_, pos, ())| pos.x == tile.x && pos.y == tile.y)
+}
+
+///Waits a turn, unless the player is already healed or too hungry to heal
+fn rest(ecs: &mut World) -> Gameplay {
+    let player_ent = *ecs.fetch::<Entity>();
+    let is_healed = ecs
+        .read_storage::<CombatStats>()
+        .get(player_ent)
+        .is_none_or(|stats| stats.hp >= stats.max_hp);
+    if is_healed {
+        ecs.fetch_mut::<GameLog>().push(&"You feel rested.");
+        return stop(ecs);
+    }
+    if player::is_hungry(ecs) {
+        ecs.fetch_mut::<GameLog>()
+            .push(&"You are too hungry to rest.");
+        return stop(ecs);
+    }
+    player::skip_turn(ecs)
+}
+
+fn stop(ecs: &World) -> Gameplay {
+    ecs.fetch_mut::<Travel>().destination = None;
+    Gameplay::AwaitingInput
+}
+
+///Hostile monsters in view and items the player hasn't seen before put a stop to travelling
+fn is_interrupted(ecs: &World) -> bool {
+    let player_ent = ecs.fetch::<Entity>();
+    let views = ecs.read_storage::<FieldOfView>();
+    let positions = ecs.read_storage::<Position>();
+    let monsters = ecs.read_storage::<Monster>();
+    let names = ecs.read_storage::<Name>();
+    let identities = ecs.fetch::<Identities>();
+    let mut logs = ecs.fetch_mut::<GameLog>();
+    let Some(view) = views.get(*player_ent) else {
+        return true;
+    };
+
+    let monster_in_view = (&ecs.entities(), &monsters, &positions, &names)
+        .join()
+        .find(|(ent, _, pos, _)| {
+            view.visible_tiles.contains(&Point::new(pos.x, pos.y))
+                && targeting::is_hostile(ecs, *ent)
+        });
+    if let Some((_, _, _, name)) = monster_in_view {
+        logs.push(&format!("You see a {}.", name.name));
+        return true;
+    }
+
+    let mut travel = ecs.fetch_mut::<Travel>();
+    let new_item = visible_items(ecs)
+        .into_iter()
+        .find(|item| !travel.known_items.contains(item));
+    if let Some(item) = new_item {
+        travel.known_items.push(item);
+        logs.push(&format!(
+            "You spot a {}.",
+            identities.name_of(names.get(item).unwrap())
+        ));
+        return true;
+    }
+    false
+}
+
+fn visible_items(ecs: &World) -> Vec<Entity> {
+    let player_ent = ecs.fetch::<Entity>();
+    let views = ecs.read_storage::<FieldOfView>();
+    let entities = ecs.entities();
+    let items = ecs.read_storage::<Item>();
+    let positions = ecs.read_storage::<Position>();
+    let hidden = ecs.read_storage::<Hidden>();
+    let Some(view) = views.get(*player_ent) else {
+        return Vec::new();
+    };
+
+    (&entities, &items, &positions, !&hidden)
+        .join()
+        .filter(|(_, _, pos, ())| view.visible_tiles.contains(&Point::new(pos.x, pos.y)))
+        .map(|(item, ..)| item)
+        .collect()
+}
+
+///The direction that leads downhill on a Dijkstra map of the destination, if any does
+fn next_step(ecs: &World, destination: PathTarget) -> Option<(i32, i32)> {
+    let map = ecs.fetch::<Map>();
+    let player_pos = *ecs.fetch::<Point>();
+    let mut logs = ecs.fetch_mut::<GameLog>();
+
+    let is_known = |idx: usize| map.is_tile_status_set(idx, TileStatus::Revealed);
+    let targets = match destination {
+        PathTarget::Unexplored => (0..map.tiles.len())
+            .filter(|idx| !is_known(*idx))
+            .collect::<Vec<_>>(),
+        PathTarget::Stairs => (0..map.tiles.len())
+            .filter(|idx| is_known(*idx) && map.tiles[*idx] == TileType::StairsDown)
+            .collect(),
+        PathTarget::Tile(target) => vec![map.xy_idx(target.x, target.y)],
+    };
+    if targets.contains(&map.xy_idx(player_pos.x, player_pos.y)) {
+        return None;
+    }
+
+    let mut dijkstra_map = DijkstraMap::new(map.width, map.height, &targets, &*map, MAX_STEPS);
+    //The targets can be given the distance back from a neighbour while the map is built
+    for idx in &targets {
+        dijkstra_map.map[*idx] = 0.0;
+    }
+
+    let mut best = (f32::MAX, None);
+    for delta_y in -1..=1 {
+        for delta_x in -1..=1 {
+            let (x, y) = (player_pos.x + delta_x, player_pos.y + delta_y);
+            if (delta_x == 0 && delta_y == 0) || x < 0 || x >= map.width || y < 0 || y >= map.height
+            {
+                continue;
+            }
+            let idx = map.xy_idx(x, y);
+            if !map.is_tile_status_set(idx, TileStatus::Blocked) && dijkstra_map.map[idx] < best.0 {
+                best = (dijkstra_map.map[idx], Some((delta_x, delta_y)));
+            }
+        }
+    }
+
+    if best.1.is_none() {
+        logs.push(match destination {
+            PathTarget::Unexplored => &"There is nowhere left to explore.",
+            PathTarget::Stairs => &"You don't know the way to the stairs.",
+            PathTarget::Tile(_) => &"You can't find a way there.",
+        });
+    }
+    best.1
+}